@@ -0,0 +1,9 @@
+CREATE TYPE scoring_rules AS (
+    sets_to_win INT,
+    set_target INT,
+    deciding_set_target INT,
+    min_lead INT,
+    point_cap INT
+);
+
+ALTER TABLE matches ADD COLUMN rules scoring_rules NOT NULL DEFAULT ROW(3, 25, 15, 2, NULL);
@@ -0,0 +1,5 @@
+ALTER TYPE scoring_rules ADD ATTRIBUTE switch_interval INT, ADD ATTRIBUTE deciding_switch_interval INT;
+
+ALTER TABLE matches ALTER COLUMN rules SET DEFAULT ROW(3, 25, 15, 2, NULL, NULL, NULL);
+
+ALTER TABLE matches ADD COLUMN side_switch_due BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,50 @@
+CREATE TYPE team AS ENUM ('A', 'B');
+
+CREATE TYPE match_event_kind AS ENUM ('STATUS', 'POINT', 'UNDO', 'SET_END', 'SWAP');
+
+CREATE TABLE match_events (
+    id SERIAL PRIMARY KEY,
+    match_id INT NOT NULL REFERENCES matches(id) ON DELETE CASCADE,
+    created_at TIMESTAMP NOT NULL DEFAULT now(),
+    kind match_event_kind NOT NULL,
+    team team,
+    status match_status,
+    reverts INT REFERENCES match_events(id) ON DELETE CASCADE
+);
+
+CREATE INDEX match_events_match_id ON match_events(match_id, id);
+
+INSERT INTO match_events(match_id, created_at, kind, status)
+SELECT id, match_start, 'STATUS', 'IN_PROGRESS' FROM matches WHERE status <> 'PLANNED' ORDER BY id;
+
+INSERT INTO match_events(match_id, created_at, kind, team)
+SELECT m.id, e.created_at, e.kind, e.team
+FROM matches m
+CROSS JOIN LATERAL generate_subscripts(m.set_results_a, 1) AS s(set_number)
+CROSS JOIN LATERAL (
+    SELECT m.match_start AS created_at, 'POINT'::match_event_kind AS kind, 'A'::team AS team, 0 AS ord, n
+    FROM generate_series(1, m.set_results_a[s.set_number]) n
+    UNION ALL
+    SELECT m.match_start, 'POINT', 'B', 1, n
+    FROM generate_series(1, m.set_results_b[s.set_number]) n
+    UNION ALL
+    SELECT
+        CASE WHEN s.set_number = array_length(m.set_results_a, 1) - 1 THEN m.set_start ELSE m.match_start END,
+        'SET_END',
+        CASE WHEN m.set_results_a[s.set_number] > m.set_results_b[s.set_number] THEN 'A' ELSE 'B' END::team,
+        2,
+        0
+    WHERE s.set_number < array_length(m.set_results_a, 1) OR m.status = 'FINISHED'
+) e
+WHERE m.status <> 'PLANNED'
+ORDER BY m.id, s.set_number, e.ord, e.n;
+
+INSERT INTO match_events(match_id, created_at, kind, status)
+SELECT id, set_start, 'STATUS', 'FINISHED' FROM matches WHERE status = 'FINISHED' ORDER BY id;
+
+INSERT INTO match_events(match_id, created_at, kind)
+SELECT id, set_start, 'SWAP' FROM matches WHERE swapped AND status = 'IN_PROGRESS' ORDER BY id;
+
+ALTER TABLE matches ALTER COLUMN status SET DEFAULT 'PLANNED';
+
+ALTER TABLE matches DROP COLUMN side_switch_due;
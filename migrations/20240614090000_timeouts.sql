@@ -0,0 +1,11 @@
+ALTER TYPE scoring_rules ADD ATTRIBUTE timeouts_per_set INT, ADD ATTRIBUTE timeout_seconds INT, ADD ATTRIBUTE technical_timeout_at INT;
+
+UPDATE matches SET rules.timeouts_per_set = 2, rules.timeout_seconds = 30;
+
+UPDATE matches SET rules.timeouts_per_set = 1, rules.technical_timeout_at = 21 WHERE (rules).switch_interval IS NOT NULL;
+
+ALTER TABLE matches ALTER COLUMN rules SET DEFAULT ROW(3, 25, 15, 2, NULL, NULL, NULL, 2, 30, NULL);
+
+ALTER TYPE match_event_kind ADD VALUE 'TIMEOUT';
+
+ALTER TYPE match_event_kind ADD VALUE 'TECHNICAL_TIMEOUT';
@@ -0,0 +1,9 @@
+ALTER TYPE scoring_rules ADD ATTRIBUTE substitutions_per_set INT;
+
+UPDATE matches SET rules.substitutions_per_set = CASE WHEN (rules).switch_interval IS NULL THEN 6 ELSE 0 END;
+
+ALTER TABLE matches ALTER COLUMN rules SET DEFAULT ROW(3, 25, 15, 2, NULL, NULL, NULL, 2, 30, NULL, 6);
+
+ALTER TYPE match_event_kind ADD VALUE 'SUBSTITUTION';
+
+ALTER TABLE match_events ADD COLUMN player_in INT, ADD COLUMN player_out INT;
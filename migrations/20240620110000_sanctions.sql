@@ -0,0 +1,5 @@
+CREATE TYPE sanction AS ENUM ('WARNING', 'PENALTY', 'EXPULSION', 'DISQUALIFICATION');
+
+ALTER TYPE match_event_kind ADD VALUE 'SANCTION';
+
+ALTER TABLE match_events ADD COLUMN sanction sanction, ADD COLUMN player INT;
@@ -0,0 +1,3 @@
+ALTER TYPE match_event_kind ADD VALUE 'FORFEIT';
+
+ALTER TYPE match_event_kind ADD VALUE 'RETIREMENT';
@@ -0,0 +1,7 @@
+ALTER TYPE match_status ADD VALUE 'POSTPONED';
+
+ALTER TYPE match_status ADD VALUE 'SUSPENDED';
+
+ALTER TYPE match_status ADD VALUE 'CANCELLED';
+
+ALTER TABLE match_events ADD COLUMN scheduled_at TIMESTAMP;
@@ -0,0 +1,6 @@
+CREATE TABLE ties (
+    id SERIAL PRIMARY KEY,
+    first_leg INT NOT NULL UNIQUE REFERENCES matches(id) ON DELETE CASCADE,
+    second_leg INT NOT NULL UNIQUE REFERENCES matches(id) ON DELETE CASCADE,
+    golden_set INT UNIQUE REFERENCES matches(id) ON DELETE SET NULL
+);
@@ -0,0 +1,3 @@
+CREATE TYPE rally_type AS ENUM ('ACE', 'ATTACK', 'BLOCK', 'OPPONENT_ERROR');
+
+ALTER TABLE match_events ADD COLUMN rally_type rally_type;
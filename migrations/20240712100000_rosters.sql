@@ -0,0 +1,10 @@
+CREATE TYPE player_role AS ENUM ('SETTER', 'OUTSIDE_HITTER', 'OPPOSITE', 'MIDDLE_BLOCKER', 'LIBERO');
+
+CREATE TABLE players (
+    id SERIAL PRIMARY KEY,
+    team VARCHAR NOT NULL,
+    number INT NOT NULL CHECK (number BETWEEN 1 AND 99),
+    name VARCHAR NOT NULL,
+    role player_role NOT NULL,
+    UNIQUE (team, number)
+);
@@ -0,0 +1,34 @@
+CREATE FUNCTION pg_temp.team_key(name VARCHAR) RETURNS VARCHAR AS $$
+    SELECT translate(
+        lower(regexp_replace(trim(name), '\s+', ' ', 'g')),
+        'ĄĆĘŁŃÓŚŹŻÁÀÂÄÃÅÉÈÊËÍÌÎÏÒÔÖÕÚÙÛÜÝŸÇÑŠŽČŘĎŤŇąćęłńóśźżáàâäãåéèêëíìîïòôöõúùûüýÿçñšžčřďťň',
+        'acelnoszzaaaaaaeeeeiiiioooouuuuyycnszcrdtnacelnoszzaaaaaaeeeeiiiioooouuuuyycnszcrdtn'
+    )
+$$ LANGUAGE SQL IMMUTABLE;
+
+CREATE TABLE teams (
+    id SERIAL PRIMARY KEY,
+    name VARCHAR(50) NOT NULL,
+    key VARCHAR(50) NOT NULL UNIQUE
+);
+
+INSERT INTO teams(name, key)
+SELECT DISTINCT ON (pg_temp.team_key(name)) regexp_replace(trim(name), '\s+', ' ', 'g'), pg_temp.team_key(name)
+FROM (SELECT team_a AS name FROM matches UNION ALL SELECT team_b FROM matches UNION ALL SELECT team FROM players) names
+ORDER BY pg_temp.team_key(name), name;
+
+ALTER TABLE matches ADD COLUMN team_a_id INT REFERENCES teams(id), ADD COLUMN team_b_id INT REFERENCES teams(id);
+UPDATE matches SET
+    team_a_id = (SELECT id FROM teams WHERE key = pg_temp.team_key(team_a)),
+    team_b_id = (SELECT id FROM teams WHERE key = pg_temp.team_key(team_b));
+ALTER TABLE matches
+    ALTER COLUMN team_a_id SET NOT NULL,
+    ALTER COLUMN team_b_id SET NOT NULL,
+    DROP COLUMN team_a,
+    DROP COLUMN team_b;
+
+ALTER TABLE players ADD COLUMN team_id INT REFERENCES teams(id) ON DELETE CASCADE;
+UPDATE players SET team_id = (SELECT id FROM teams WHERE key = pg_temp.team_key(team));
+DELETE FROM players p USING players q WHERE p.team_id = q.team_id AND p.number = q.number AND p.id > q.id;
+ALTER TABLE players ALTER COLUMN team_id SET NOT NULL, DROP COLUMN team;
+ALTER TABLE players ADD UNIQUE (team_id, number);
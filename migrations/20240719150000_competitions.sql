@@ -0,0 +1,6 @@
+CREATE TABLE competitions (
+    id SERIAL PRIMARY KEY,
+    name VARCHAR(50) NOT NULL UNIQUE
+);
+
+ALTER TABLE matches ADD COLUMN competition_id INT REFERENCES competitions(id) ON DELETE SET NULL;
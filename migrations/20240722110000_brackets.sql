@@ -0,0 +1,18 @@
+CREATE TABLE brackets (
+    id SERIAL PRIMARY KEY,
+    name VARCHAR(50) NOT NULL UNIQUE,
+    first_round TIMESTAMP NOT NULL,
+    round_interval INT NOT NULL,
+    rules scoring_rules NOT NULL,
+    competition_id INT REFERENCES competitions(id) ON DELETE SET NULL
+);
+
+CREATE TABLE bracket_nodes (
+    bracket_id INT NOT NULL REFERENCES brackets(id) ON DELETE CASCADE,
+    round INT NOT NULL,
+    position INT NOT NULL,
+    team_a_id INT REFERENCES teams(id),
+    team_b_id INT REFERENCES teams(id),
+    match_id INT UNIQUE REFERENCES matches(id) ON DELETE SET NULL,
+    PRIMARY KEY (bracket_id, round, position)
+);
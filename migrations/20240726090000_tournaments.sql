@@ -0,0 +1,25 @@
+CREATE TYPE tournament_format AS ENUM ('DOUBLE_ELIMINATION', 'SWISS');
+
+CREATE TABLE tournaments (
+    id SERIAL PRIMARY KEY,
+    name VARCHAR(50) NOT NULL UNIQUE,
+    format tournament_format NOT NULL,
+    rules scoring_rules NOT NULL,
+    competition_id INT REFERENCES competitions(id) ON DELETE SET NULL
+);
+
+CREATE TABLE tournament_teams (
+    tournament_id INT NOT NULL REFERENCES tournaments(id) ON DELETE CASCADE,
+    team_id INT NOT NULL REFERENCES teams(id),
+    seed INT NOT NULL,
+    PRIMARY KEY (tournament_id, team_id)
+);
+
+CREATE TABLE tournament_pairings (
+    id SERIAL PRIMARY KEY,
+    tournament_id INT NOT NULL REFERENCES tournaments(id) ON DELETE CASCADE,
+    round INT NOT NULL,
+    team_a_id INT NOT NULL REFERENCES teams(id),
+    team_b_id INT REFERENCES teams(id),
+    match_id INT UNIQUE REFERENCES matches(id) ON DELETE CASCADE
+);
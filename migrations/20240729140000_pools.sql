@@ -0,0 +1,21 @@
+CREATE TYPE tie_breaker AS ENUM ('MATCH_POINTS', 'SETS_RATIO', 'POINTS_RATIO', 'HEAD_TO_HEAD');
+
+CREATE TABLE pool_plays (
+    id SERIAL PRIMARY KEY,
+    name VARCHAR(50) NOT NULL UNIQUE,
+    tie_breakers tie_breaker[] NOT NULL,
+    advancing INT NOT NULL,
+    competition_id INT REFERENCES competitions(id) ON DELETE SET NULL,
+    bracket_id INT REFERENCES brackets(id) ON DELETE SET NULL
+);
+
+CREATE TABLE pools (
+    id SERIAL PRIMARY KEY,
+    pool_play_id INT NOT NULL REFERENCES pool_plays(id) ON DELETE CASCADE,
+    name VARCHAR(50) NOT NULL
+);
+
+CREATE TABLE pool_matches (
+    pool_id INT NOT NULL REFERENCES pools(id) ON DELETE CASCADE,
+    match_id INT NOT NULL UNIQUE REFERENCES matches(id) ON DELETE CASCADE
+);
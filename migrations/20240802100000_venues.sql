@@ -0,0 +1,14 @@
+CREATE TABLE venues (
+    id SERIAL PRIMARY KEY,
+    name VARCHAR(50) NOT NULL UNIQUE,
+    slot_minutes INT NOT NULL
+);
+
+CREATE TABLE courts (
+    id SERIAL PRIMARY KEY,
+    venue_id INT NOT NULL REFERENCES venues(id) ON DELETE CASCADE,
+    name VARCHAR(50) NOT NULL,
+    UNIQUE (venue_id, name)
+);
+
+ALTER TABLE matches ADD COLUMN court_id INT REFERENCES courts(id) ON DELETE SET NULL;
@@ -0,0 +1,15 @@
+CREATE TYPE official_role AS ENUM ('FIRST_REFEREE', 'SECOND_REFEREE', 'SCORER', 'LINE_JUDGE');
+
+CREATE TABLE officials (
+    id SERIAL PRIMARY KEY,
+    name VARCHAR(50) NOT NULL UNIQUE
+);
+
+CREATE TABLE match_officials (
+    match_id INT NOT NULL REFERENCES matches(id) ON DELETE CASCADE,
+    official_id INT NOT NULL REFERENCES officials(id) ON DELETE CASCADE,
+    role official_role NOT NULL,
+    PRIMARY KEY (match_id, official_id)
+);
+
+CREATE UNIQUE INDEX match_officials_single_role ON match_officials(match_id, role) WHERE role <> 'LINE_JUDGE';
@@ -0,0 +1,19 @@
+CREATE TABLE seasons (
+    id SERIAL PRIMARY KEY,
+    name VARCHAR(50) NOT NULL UNIQUE,
+    started_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+INSERT INTO seasons(name, started_at) SELECT 'Season 1', COALESCE(min(match_start), now()) FROM matches;
+
+-- The latest season is the current one, every earlier season is archived
+CREATE FUNCTION current_season() RETURNS INT AS $$
+    SELECT max(id) FROM seasons
+$$ LANGUAGE SQL STABLE;
+
+ALTER TABLE matches ADD COLUMN season_id INT NOT NULL DEFAULT current_season() REFERENCES seasons(id);
+
+ALTER TABLE competitions
+    ADD COLUMN season_id INT NOT NULL DEFAULT current_season() REFERENCES seasons(id),
+    DROP CONSTRAINT competitions_name_key,
+    ADD UNIQUE (season_id, name);
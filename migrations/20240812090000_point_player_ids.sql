@@ -0,0 +1,10 @@
+ALTER TABLE match_events ADD COLUMN player_id INT REFERENCES players(id) ON DELETE SET NULL;
+
+UPDATE match_events e SET player_id = p.id
+FROM matches m, players p
+WHERE e.match_id = m.id
+    AND e.kind = 'POINT'
+    AND p.team_id = CASE e.team WHEN 'A' THEN m.team_a_id ELSE m.team_b_id END
+    AND p.number = e.player;
+
+UPDATE match_events SET player = NULL WHERE kind = 'POINT';
@@ -0,0 +1,452 @@
+use axum::{
+    body::Bytes,
+    extract::{
+        rejection::{JsonRejection, PathRejection, QueryRejection},
+        ws::Message,
+        Path, Query, State,
+    },
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::{Postgres, Transaction};
+use time::format_description;
+
+use crate::{countdown, db, markup, rules::PRESETS, AppState, ClientView};
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/matches", get(list_matches).post(create_match))
+        .route("/matches/:id", get(read_match).delete(delete_match))
+        .route("/matches/:id/start", post(start_match))
+        .route("/matches/:id/points/:team", post(add_point).delete(remove_point))
+        .route("/matches/:id/timeouts/:team", post(take_timeout))
+        .route("/matches/:id/end_set", post(end_set))
+        .route("/matches/:id/undo", post(undo))
+        .route("/matches/:id/status", post(change_status))
+}
+
+#[derive(Serialize)]
+struct MatchJson {
+    id: i32,
+    team_a: String,
+    team_b: String,
+    team_a_id: i32,
+    team_b_id: i32,
+    match_start: String,
+    status: db::MatchStatus,
+    rules: Option<&'static str>,
+    result: Vec<i32>,
+    set_results_a: Vec<i32>,
+    set_results_b: Vec<i32>,
+    serving: Option<db::Team>,
+    timeouts: [i32; 2],
+    competition_id: Option<i32>,
+    court: Option<String>,
+    season_id: i32,
+    archived: bool,
+}
+
+impl From<db::Match> for MatchJson {
+    fn from(match_info: db::Match) -> MatchJson {
+        let format = format_description::parse("[year]-[month]-[day]T[hour]:[minute]:[second]").unwrap();
+        MatchJson {
+            id: match_info.id,
+            match_start: match_info.match_start.format(&format).unwrap(),
+            status: match_info.status,
+            rules: PRESETS
+                .iter()
+                .find(|(_, rules)| *rules == match_info.rules)
+                .map(|(name, _)| *name),
+            result: match_info.result,
+            serving: match_info.serving,
+            timeouts: match_info.timeouts,
+            competition_id: match_info.competition_id,
+            season_id: match_info.season_id,
+            archived: match_info.archived,
+            team_a_id: match_info.team_a_id,
+            team_b_id: match_info.team_b_id,
+            team_a: match_info.team_a,
+            team_b: match_info.team_b,
+            set_results_a: match_info.set_results_a,
+            set_results_b: match_info.set_results_b,
+            court: match_info.court,
+        }
+    }
+}
+
+/// Error body of every failed request: `{"error": {"code": ..., "message": ...}}`
+struct ApiError {
+    status: StatusCode,
+    code: &'static str,
+    message: String,
+}
+
+#[derive(Serialize)]
+struct ErrorBody {
+    error: ErrorDetail,
+}
+
+#[derive(Serialize)]
+struct ErrorDetail {
+    code: &'static str,
+    message: String,
+}
+
+impl ApiError {
+    fn new(status: StatusCode, code: &'static str, message: impl Into<String>) -> ApiError {
+        ApiError {
+            status,
+            code,
+            message: message.into(),
+        }
+    }
+
+    fn not_found() -> ApiError {
+        ApiError::new(StatusCode::NOT_FOUND, "NOT_FOUND", "Match not found")
+    }
+
+    fn in_bracket() -> ApiError {
+        ApiError::new(
+            StatusCode::CONFLICT,
+            "IN_BRACKET",
+            "A bracket match cannot be cancelled or deleted, record a forfeit instead",
+        )
+    }
+
+    fn not_allowed() -> ApiError {
+        ApiError::new(
+            StatusCode::CONFLICT,
+            "ACTION_NOT_ALLOWED",
+            "The action is not allowed in the current state of the match",
+        )
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let body = ErrorBody {
+            error: ErrorDetail {
+                code: self.code,
+                message: self.message,
+            },
+        };
+        (self.status, Json(body)).into_response()
+    }
+}
+
+impl From<db::MatchAddError> for ApiError {
+    fn from(e: db::MatchAddError) -> ApiError {
+        let (status, code) = match e {
+            db::MatchAddError::TeamNameEmpty => (StatusCode::UNPROCESSABLE_ENTITY, "TEAM_NAME_EMPTY"),
+            db::MatchAddError::TeamNameTooLong => {
+                (StatusCode::UNPROCESSABLE_ENTITY, "TEAM_NAME_TOO_LONG")
+            }
+            db::MatchAddError::DuplicateTeamName => {
+                (StatusCode::UNPROCESSABLE_ENTITY, "DUPLICATE_TEAM_NAME")
+            }
+            db::MatchAddError::PastDate => (StatusCode::UNPROCESSABLE_ENTITY, "PAST_DATE"),
+            db::MatchAddError::IncorrectDateFormat => {
+                (StatusCode::UNPROCESSABLE_ENTITY, "INCORRECT_DATE_FORMAT")
+            }
+            db::MatchAddError::UnknownRuleset => (StatusCode::UNPROCESSABLE_ENTITY, "UNKNOWN_RULESET"),
+            db::MatchAddError::UnknownCompetition => {
+                (StatusCode::UNPROCESSABLE_ENTITY, "UNKNOWN_COMPETITION")
+            }
+            db::MatchAddError::UnknownCourt => (StatusCode::UNPROCESSABLE_ENTITY, "UNKNOWN_COURT"),
+            db::MatchAddError::CourtDoubleBooked => (StatusCode::CONFLICT, "COURT_DOUBLE_BOOKED"),
+            db::MatchAddError::TeamDoubleBooked => (StatusCode::CONFLICT, "TEAM_DOUBLE_BOOKED"),
+            db::MatchAddError::OfficialDoubleBooked => {
+                (StatusCode::CONFLICT, "OFFICIAL_DOUBLE_BOOKED")
+            }
+        };
+        ApiError::new(status, code, e.to_string())
+    }
+}
+
+impl From<PathRejection> for ApiError {
+    fn from(rejection: PathRejection) -> ApiError {
+        ApiError::new(rejection.status(), "INVALID_PATH", rejection.body_text())
+    }
+}
+
+impl From<QueryRejection> for ApiError {
+    fn from(rejection: QueryRejection) -> ApiError {
+        ApiError::new(rejection.status(), "INVALID_QUERY", rejection.body_text())
+    }
+}
+
+impl From<JsonRejection> for ApiError {
+    fn from(rejection: JsonRejection) -> ApiError {
+        ApiError::new(rejection.status(), "INVALID_BODY", rejection.body_text())
+    }
+}
+
+/// Pushes a changed match to every live view showing it, like the htmx handlers do
+async fn broadcast(state: &AppState, match_info: &db::Match, tie: Option<db::Tie>) {
+    state
+        .clients
+        .send_to_clients(
+            ClientView::MatchPage(match_info.id),
+            &Message::Text(markup::match_page_update(match_info).into_string()),
+        )
+        .await;
+    state
+        .clients
+        .send_to_clients(
+            ClientView::MainPage,
+            &Message::Text(markup::update_match_entry(match_info).into_string()),
+        )
+        .await;
+    crate::send_team_update(state, [match_info.team_a_id, match_info.team_b_id]).await;
+    countdown::sync(state, match_info).await;
+    crate::send_tie_update(state, tie).await;
+    if match_info.status == db::MatchStatus::Finished {
+        crate::send_standings_update(state, match_info).await;
+    }
+    crate::send_bracket_update(state, match_info).await;
+    crate::send_tournament_update(state, match_info).await;
+}
+
+#[derive(Deserialize)]
+struct ListQuery {
+    season: Option<i32>,
+}
+
+async fn list_matches(
+    State(state): State<AppState>,
+    query: Result<Query<ListQuery>, QueryRejection>,
+) -> Result<Json<Vec<MatchJson>>, ApiError> {
+    let Query(query) = query?;
+    let mut conn = state.pool.acquire().await.unwrap();
+    let season = match query.season {
+        Some(season_id) => db::get_season(&mut conn, season_id)
+            .await
+            .ok_or_else(|| ApiError::new(StatusCode::NOT_FOUND, "NOT_FOUND", "Season not found"))?,
+        None => db::current_season(&mut conn).await,
+    };
+    let matches = db::get_matches(&mut conn, season.id).await;
+    Ok(Json(matches.into_iter().map(MatchJson::from).collect()))
+}
+
+async fn read_match(
+    State(state): State<AppState>,
+    path: Result<Path<i32>, PathRejection>,
+) -> Result<Json<MatchJson>, ApiError> {
+    let Path(id) = path?;
+    let mut conn = state.pool.acquire().await.unwrap();
+    if !db::match_exists(&mut conn, id).await {
+        return Err(ApiError::not_found());
+    }
+    Ok(Json(db::get_match(&mut conn, id).await.into()))
+}
+
+#[derive(Deserialize)]
+struct NewMatch {
+    team_a: String,
+    team_b: String,
+    #[serde(default)]
+    date: String,
+    rules: String,
+    #[serde(default)]
+    competition: Option<i32>,
+    #[serde(default)]
+    court: Option<i32>,
+}
+
+async fn create_match(
+    State(state): State<AppState>,
+    body: Result<Json<NewMatch>, JsonRejection>,
+) -> Result<(StatusCode, Json<MatchJson>), ApiError> {
+    let Json(new_match) = body?;
+    let mut tx = state.pool.begin().await.unwrap();
+    let match_info = db::add_match(
+        &mut tx,
+        &new_match.team_a,
+        &new_match.team_b,
+        &new_match.date,
+        &new_match.rules,
+        &new_match.competition.map(|id| id.to_string()).unwrap_or_default(),
+        &new_match.court.map(|id| id.to_string()).unwrap_or_default(),
+    )
+    .await?;
+    tx.commit().await.unwrap();
+    state
+        .clients
+        .send_to_clients(
+            ClientView::MainPage,
+            &Message::Text(markup::add_match_entry(&match_info).into_string()),
+        )
+        .await;
+    crate::send_team_update(&state, [match_info.team_a_id, match_info.team_b_id]).await;
+    Ok((StatusCode::CREATED, Json(match_info.into())))
+}
+
+async fn delete_match(
+    State(state): State<AppState>,
+    path: Result<Path<i32>, PathRejection>,
+) -> Result<StatusCode, ApiError> {
+    let Path(id) = path?;
+    let mut conn = state.pool.acquire().await.unwrap();
+    if !db::match_exists(&mut conn, id).await {
+        return Err(ApiError::not_found());
+    }
+    if db::in_bracket(&mut conn, id).await {
+        return Err(ApiError::in_bracket());
+    }
+    let match_info = db::get_match(&mut conn, id).await;
+    if !db::remove_match(&mut conn, id).await {
+        return Err(ApiError::new(
+            StatusCode::CONFLICT,
+            "ARCHIVED",
+            "Matches of archived seasons are read-only",
+        ));
+    }
+    drop(conn);
+    state
+        .clients
+        .send_to_clients(
+            ClientView::MainPage,
+            &Message::Text(markup::remove_match_entry(id).into_string()),
+        )
+        .await;
+    state
+        .clients
+        .send_to_clients(
+            ClientView::MatchPage(id),
+            &Message::Text(markup::remove_match_page().into_string()),
+        )
+        .await;
+    crate::send_team_update(&state, [match_info.team_a_id, match_info.team_b_id]).await;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Opens the transaction for a scoring action on an existing match
+async fn begin(state: &AppState, id: i32) -> Result<Transaction<'static, Postgres>, ApiError> {
+    let mut tx = state.pool.begin().await.unwrap();
+    if !db::match_exists(&mut tx, id).await {
+        return Err(ApiError::not_found());
+    }
+    Ok(tx)
+}
+
+/// Commits a scoring action and answers with the updated match, or with an error
+/// when the match does not allow the action in its current state
+async fn finish(
+    state: &AppState,
+    mut tx: Transaction<'static, Postgres>,
+    id: i32,
+    done: bool,
+) -> Result<Json<MatchJson>, ApiError> {
+    if !done {
+        return Err(ApiError::not_allowed());
+    }
+    let match_info = db::get_match(&mut tx, id).await;
+    let tie = db::get_tie(&mut tx, id).await;
+    tx.commit().await.unwrap();
+    broadcast(state, &match_info, tie).await;
+    Ok(Json(match_info.into()))
+}
+
+#[derive(Deserialize)]
+struct StartBody {
+    serve: db::Team,
+}
+
+async fn start_match(
+    State(state): State<AppState>,
+    path: Result<Path<i32>, PathRejection>,
+    body: Result<Json<StartBody>, JsonRejection>,
+) -> Result<Json<MatchJson>, ApiError> {
+    let Path(id) = path?;
+    let Json(body) = body?;
+    let mut tx = begin(&state, id).await?;
+    let done = db::start_match(&mut tx, id, body.serve).await;
+    finish(&state, tx, id, done).await
+}
+
+#[derive(Deserialize)]
+struct PointBody {
+    rally_type: Option<db::RallyType>,
+    player_id: Option<i32>,
+}
+
+async fn add_point(
+    State(state): State<AppState>,
+    path: Result<Path<(i32, db::Team)>, PathRejection>,
+    body: Bytes,
+) -> Result<Json<MatchJson>, ApiError> {
+    let Path((id, team)) = path?;
+    // Only a request without a body records an untagged point
+    let (rally_type, player_id) = if body.is_empty() {
+        (None, None)
+    } else {
+        let Json(body) = Json::<PointBody>::from_bytes(&body)?;
+        (body.rally_type, body.player_id)
+    };
+    let mut tx = begin(&state, id).await?;
+    let done = db::add_set_point(&mut tx, id, team, rally_type, player_id).await;
+    finish(&state, tx, id, done).await
+}
+
+async fn remove_point(
+    State(state): State<AppState>,
+    path: Result<Path<(i32, db::Team)>, PathRejection>,
+) -> Result<Json<MatchJson>, ApiError> {
+    let Path((id, team)) = path?;
+    let mut tx = begin(&state, id).await?;
+    let done = db::remove_set_point(&mut tx, id, team).await;
+    finish(&state, tx, id, done).await
+}
+
+async fn take_timeout(
+    State(state): State<AppState>,
+    path: Result<Path<(i32, db::Team)>, PathRejection>,
+) -> Result<Json<MatchJson>, ApiError> {
+    let Path((id, team)) = path?;
+    let mut tx = begin(&state, id).await?;
+    let done = db::take_timeout(&mut tx, id, team).await;
+    finish(&state, tx, id, done).await
+}
+
+async fn end_set(
+    State(state): State<AppState>,
+    path: Result<Path<i32>, PathRejection>,
+) -> Result<Json<MatchJson>, ApiError> {
+    let Path(id) = path?;
+    let mut tx = begin(&state, id).await?;
+    let done = db::end_set(&mut tx, id).await;
+    finish(&state, tx, id, done).await
+}
+
+async fn undo(
+    State(state): State<AppState>,
+    path: Result<Path<i32>, PathRejection>,
+) -> Result<Json<MatchJson>, ApiError> {
+    let Path(id) = path?;
+    let mut tx = begin(&state, id).await?;
+    let done = db::undo(&mut tx, id).await;
+    finish(&state, tx, id, done).await
+}
+
+#[derive(Deserialize)]
+struct StatusBody {
+    status: db::MatchStatus,
+}
+
+async fn change_status(
+    State(state): State<AppState>,
+    path: Result<Path<i32>, PathRejection>,
+    body: Result<Json<StatusBody>, JsonRejection>,
+) -> Result<Json<MatchJson>, ApiError> {
+    let Path(id) = path?;
+    let Json(body) = body?;
+    let mut tx = begin(&state, id).await?;
+    if body.status == db::MatchStatus::Cancelled && db::in_bracket(&mut tx, id).await {
+        return Err(ApiError::in_bracket());
+    }
+    let done = db::change_status(&mut tx, id, body.status).await;
+    finish(&state, tx, id, done).await
+}
@@ -0,0 +1,54 @@
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use axum::extract::ws::Message;
+use tokio::{sync::Mutex, task::AbortHandle, time::interval};
+
+use crate::{db, markup};
+
+#[derive(Default, Clone)]
+pub struct Countdowns(Arc<Mutex<HashMap<i32, (i32, AbortHandle)>>>);
+
+pub async fn sync(state: &crate::AppState, match_info: &db::Match) {
+    let mut countdowns = state.countdowns.0.lock().await;
+    let timeout = match_info.running_timeout();
+    if let Some((event_id, handle)) = countdowns.get(&match_info.id) {
+        if timeout.is_some_and(|timeout| timeout.event_id == *event_id) {
+            return;
+        }
+        handle.abort();
+        countdowns.remove(&match_info.id);
+    }
+    let Some(timeout) = timeout.cloned() else {
+        return;
+    };
+    let label = markup::timeout_label(match_info);
+    let match_id = match_info.id;
+    let event_id = timeout.event_id;
+    let task_state = state.clone();
+    let handle = tokio::spawn(async move {
+        let mut ticks = interval(Duration::from_secs(1));
+        loop {
+            ticks.tick().await;
+            let remaining = timeout.remaining();
+            task_state
+                .clients
+                .send_to_clients(
+                    crate::ClientView::MatchPage(match_id),
+                    &Message::Text(markup::timeout_countdown(&label, remaining).into_string()),
+                )
+                .await;
+            if remaining == 0 {
+                break;
+            }
+        }
+        let mut countdowns = task_state.countdowns.0.lock().await;
+        if countdowns
+            .get(&match_id)
+            .is_some_and(|(running_event_id, _)| *running_event_id == event_id)
+        {
+            countdowns.remove(&match_id);
+        }
+    })
+    .abort_handle();
+    countdowns.insert(match_id, (event_id, handle));
+}
@@ -1,6 +1,17 @@
-use sqlx::{query, query_as, PgConnection, QueryBuilder, Type};
-use std::{error::Error, fmt::Display};
-use time::{format_description::well_known::Iso8601, OffsetDateTime, PrimitiveDateTime};
+use crate::rules::{ScoringRules, SetOutcome, SET_MINUTES};
+use serde::{Deserialize, Serialize};
+use sqlx::{
+    postgres::{PgHasArrayType, PgTypeInfo},
+    query, query_as, PgConnection, Type,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    error::Error,
+    fmt::Display,
+};
+use time::{
+    format_description::well_known::Iso8601, Duration, OffsetDateTime, PrimitiveDateTime,
+};
 
 pub struct Match {
     pub id: i32,
@@ -8,19 +19,607 @@ pub struct Match {
     pub set_start: PrimitiveDateTime,
     pub team_a: String,
     pub team_b: String,
+    pub team_a_id: i32,
+    pub team_b_id: i32,
+    pub competition_id: Option<i32>,
+    pub court_id: Option<i32>,
+    pub court: Option<String>,
+    pub season_id: i32,
+    pub archived: bool,
     pub swapped: bool,
     pub result: Vec<i32>,
     pub set_results_a: Vec<i32>,
     pub set_results_b: Vec<i32>,
     pub status: MatchStatus,
+    pub rules: ScoringRules,
+    pub side_switch_due: bool,
+    pub serving: Option<Team>,
+    pub set_first_server: Option<Team>,
+    pub rotation: [i32; 2],
+    pub timeouts: [i32; 2],
+    pub timeout: Option<Timeout>,
+    pub technical_timeout_taken: bool,
+    pub substitutions: Vec<Substitution>,
+    pub sanctions: Vec<SanctionRecord>,
+    pub outcome: Option<Outcome>,
+    pub rosters: [Vec<Player>; 2],
+    pub officials: Vec<MatchOfficial>,
+    pub history: Vec<MatchEvent>,
 }
 
-#[derive(Type, PartialEq, Eq, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Outcome {
+    Forfeit(Team),
+    Retirement(Team),
+}
+
+#[derive(Clone)]
+pub struct Timeout {
+    pub event_id: i32,
+    pub team: Option<Team>,
+    pub started: PrimitiveDateTime,
+    pub seconds: i32,
+}
+
+pub struct Substitution {
+    pub team: Team,
+    pub set: usize,
+    pub player_in: i32,
+    pub player_out: i32,
+    pub score: [i32; 2],
+}
+
+#[derive(Type, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[sqlx(type_name = "sanction", rename_all = "SCREAMING_SNAKE_CASE")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum Sanction {
+    Warning,
+    Penalty,
+    Expulsion,
+    Disqualification,
+}
+
+#[derive(Type, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[sqlx(type_name = "rally_type", rename_all = "SCREAMING_SNAKE_CASE")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum RallyType {
+    Ace,
+    Attack,
+    Block,
+    OpponentError,
+}
+
+impl RallyType {
+    pub const ALL: [RallyType; 4] = [
+        RallyType::Ace,
+        RallyType::Attack,
+        RallyType::Block,
+        RallyType::OpponentError,
+    ];
+}
+
+#[derive(Type, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[sqlx(type_name = "tournament_format", rename_all = "SCREAMING_SNAKE_CASE")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum TournamentFormat {
+    DoubleElimination,
+    Swiss,
+}
+
+#[derive(Type, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[sqlx(type_name = "tie_breaker", rename_all = "SCREAMING_SNAKE_CASE")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum TieBreaker {
+    MatchPoints,
+    SetsRatio,
+    PointsRatio,
+    HeadToHead,
+}
+
+impl TieBreaker {
+    pub const ALL: [TieBreaker; 4] = [
+        TieBreaker::MatchPoints,
+        TieBreaker::SetsRatio,
+        TieBreaker::PointsRatio,
+        TieBreaker::HeadToHead,
+    ];
+}
+
+impl PgHasArrayType for TieBreaker {
+    fn array_type_info() -> PgTypeInfo {
+        PgTypeInfo::with_name("_tie_breaker")
+    }
+}
+
+#[derive(Type, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[sqlx(type_name = "player_role", rename_all = "SCREAMING_SNAKE_CASE")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum PlayerRole {
+    Setter,
+    OutsideHitter,
+    Opposite,
+    MiddleBlocker,
+    Libero,
+}
+
+#[derive(Clone)]
+pub struct Player {
+    pub id: i32,
+    pub team_id: i32,
+    pub team: String,
+    pub number: i32,
+    pub name: String,
+    pub role: PlayerRole,
+}
+
+#[derive(Type, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[sqlx(type_name = "official_role", rename_all = "SCREAMING_SNAKE_CASE")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum OfficialRole {
+    FirstReferee,
+    SecondReferee,
+    Scorer,
+    LineJudge,
+}
+
+pub struct Official {
+    pub id: i32,
+    pub name: String,
+}
+
+pub struct MatchOfficial {
+    pub match_id: i32,
+    pub official_id: i32,
+    pub name: String,
+    pub role: OfficialRole,
+}
+
+pub struct SanctionRecord {
+    pub team: Team,
+    pub player: Option<i32>,
+    pub sanction: Sanction,
+    pub set: usize,
+    pub score: [i32; 2],
+}
+
+impl Timeout {
+    pub fn remaining(&self) -> i32 {
+        let elapsed = OffsetDateTime::now_utc() - self.started.assume_utc();
+        (Duration::seconds(self.seconds.into()) - elapsed)
+            .whole_seconds()
+            .clamp(0, self.seconds.into()) as i32
+    }
+}
+
+struct MatchRow {
+    id: i32,
+    match_start: PrimitiveDateTime,
+    team_a: String,
+    team_b: String,
+    team_a_id: i32,
+    team_b_id: i32,
+    competition_id: Option<i32>,
+    court_id: Option<i32>,
+    court: Option<String>,
+    season_id: i32,
+    archived: bool,
+    rules: ScoringRules,
+}
+
+pub struct Competition {
+    pub id: i32,
+    pub name: String,
+}
+
+#[derive(Type, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
 #[sqlx(type_name = "match_status", rename_all = "SCREAMING_SNAKE_CASE")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum MatchStatus {
     Finished,
     Planned,
     InProgress,
+    Postponed,
+    Suspended,
+    Cancelled,
+}
+
+impl MatchStatus {
+    pub fn can_change_to(self, status: MatchStatus) -> bool {
+        matches!(
+            (self, status),
+            (MatchStatus::InProgress, MatchStatus::Suspended)
+                | (MatchStatus::Suspended, MatchStatus::InProgress)
+                | (MatchStatus::Planned, MatchStatus::Postponed)
+                | (
+                    MatchStatus::Planned | MatchStatus::Postponed | MatchStatus::Suspended,
+                    MatchStatus::Cancelled
+                )
+        )
+    }
+}
+
+#[derive(Type, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[sqlx(type_name = "team", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum Team {
+    A,
+    B,
+}
+
+impl Team {
+    pub fn index(self) -> usize {
+        match self {
+            Team::A => 0,
+            Team::B => 1,
+        }
+    }
+
+    pub fn from_index(index: usize) -> Team {
+        if index == 0 {
+            Team::A
+        } else {
+            Team::B
+        }
+    }
+
+    pub fn opponent(self) -> Team {
+        match self {
+            Team::A => Team::B,
+            Team::B => Team::A,
+        }
+    }
+}
+
+#[derive(Type, Clone, Copy, PartialEq, Eq, Debug)]
+#[sqlx(type_name = "match_event_kind", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum EventKind {
+    Status,
+    Point,
+    Undo,
+    SetEnd,
+    Swap,
+    Timeout,
+    TechnicalTimeout,
+    Substitution,
+    Sanction,
+    Forfeit,
+    Retirement,
+}
+
+pub struct MatchEvent {
+    pub id: i32,
+    pub match_id: i32,
+    pub created_at: PrimitiveDateTime,
+    pub kind: EventKind,
+    pub team: Option<Team>,
+    pub status: Option<MatchStatus>,
+    pub reverts: Option<i32>,
+    pub player_in: Option<i32>,
+    pub player_out: Option<i32>,
+    pub sanction: Option<Sanction>,
+    pub player: Option<i32>,
+    pub scheduled_at: Option<PrimitiveDateTime>,
+    pub rally_type: Option<RallyType>,
+    pub player_id: Option<i32>,
+}
+
+#[derive(Default)]
+struct NewEvent {
+    team: Option<Team>,
+    status: Option<MatchStatus>,
+    reverts: Option<i32>,
+    player_in: Option<i32>,
+    player_out: Option<i32>,
+    sanction: Option<Sanction>,
+    player: Option<i32>,
+    scheduled_at: Option<PrimitiveDateTime>,
+    rally_type: Option<RallyType>,
+    player_id: Option<i32>,
+}
+
+impl Match {
+    fn project(row: MatchRow, events: Vec<MatchEvent>) -> Match {
+        let reverted: HashSet<i32> = events.iter().filter_map(|event| event.reverts).collect();
+        let mut match_info = Match {
+            id: row.id,
+            match_start: row.match_start,
+            set_start: row.match_start,
+            team_a: row.team_a,
+            team_b: row.team_b,
+            team_a_id: row.team_a_id,
+            team_b_id: row.team_b_id,
+            competition_id: row.competition_id,
+            court_id: row.court_id,
+            court: row.court,
+            season_id: row.season_id,
+            archived: row.archived,
+            swapped: false,
+            result: vec![0, 0],
+            set_results_a: vec![0],
+            set_results_b: vec![0],
+            status: MatchStatus::Planned,
+            rules: row.rules,
+            side_switch_due: false,
+            serving: None,
+            set_first_server: None,
+            rotation: [0, 0],
+            timeouts: [0, 0],
+            timeout: None,
+            technical_timeout_taken: false,
+            substitutions: Vec::new(),
+            sanctions: Vec::new(),
+            outcome: None,
+            rosters: [Vec::new(), Vec::new()],
+            officials: Vec::new(),
+            history: Vec::new(),
+        };
+        for event in events {
+            if event.kind == EventKind::Undo || reverted.contains(&event.id) {
+                continue;
+            }
+            match_info.apply(&event);
+            match_info.history.push(event);
+        }
+        match_info
+    }
+
+    fn apply(&mut self, event: &MatchEvent) {
+        match event.kind {
+            EventKind::Status => {
+                let status = event.status.unwrap();
+                if status == MatchStatus::InProgress && self.status == MatchStatus::Planned {
+                    self.match_start = event.created_at;
+                    self.set_start = event.created_at;
+                    self.serving = event.team;
+                    self.set_first_server = event.team;
+                }
+                if let Some(scheduled_at) = event.scheduled_at {
+                    self.match_start = scheduled_at;
+                    self.set_start = scheduled_at;
+                }
+                if status == MatchStatus::Finished {
+                    self.swapped = false;
+                }
+                self.timeout = None;
+                self.status = status;
+            }
+            EventKind::Point => {
+                let team = event.team.unwrap();
+                *self.set_results_mut(team).last_mut().unwrap() += 1;
+                if self.serving == Some(team.opponent()) {
+                    self.rotation[team.index()] = (self.rotation[team.index()] + 1) % 6;
+                }
+                self.serving = Some(team);
+                self.timeout = None;
+                self.side_switch_due = self.rules.side_switch_due(
+                    &self.result,
+                    self.set_points(Team::A),
+                    self.set_points(Team::B),
+                );
+            }
+            EventKind::SetEnd => {
+                let result_index = event.team.unwrap().index();
+                self.result[result_index] += 1;
+                if self.result[result_index] < self.rules.sets_to_win {
+                    self.set_results_a.push(0);
+                    self.set_results_b.push(0);
+                    self.set_start = event.created_at;
+                    self.set_first_server = self.set_first_server.map(Team::opponent);
+                    self.serving = self.set_first_server;
+                    self.rotation = [0, 0];
+                }
+                self.side_switch_due = false;
+                self.timeouts = [0, 0];
+                self.timeout = None;
+                self.technical_timeout_taken = false;
+            }
+            EventKind::Swap => {
+                self.swapped = !self.swapped;
+                self.side_switch_due = false;
+            }
+            EventKind::Timeout => {
+                self.timeouts[event.team.unwrap().index()] += 1;
+                self.timeout = Some(Timeout {
+                    event_id: event.id,
+                    team: event.team,
+                    started: event.created_at,
+                    seconds: self.rules.timeout_seconds,
+                });
+            }
+            EventKind::TechnicalTimeout => {
+                self.technical_timeout_taken = true;
+                self.timeout = Some(Timeout {
+                    event_id: event.id,
+                    team: None,
+                    started: event.created_at,
+                    seconds: self.rules.timeout_seconds,
+                });
+            }
+            EventKind::Substitution => {
+                self.substitutions.push(Substitution {
+                    team: event.team.unwrap(),
+                    set: self.set_results_a.len(),
+                    player_in: event.player_in.unwrap(),
+                    player_out: event.player_out.unwrap(),
+                    score: [self.set_points(Team::A), self.set_points(Team::B)],
+                });
+            }
+            EventKind::Sanction => {
+                self.sanctions.push(SanctionRecord {
+                    team: event.team.unwrap(),
+                    player: event.player,
+                    sanction: event.sanction.unwrap(),
+                    set: self.set_results_a.len(),
+                    score: [self.set_points(Team::A), self.set_points(Team::B)],
+                });
+            }
+            EventKind::Forfeit | EventKind::Retirement => {
+                let team = event.team.unwrap();
+                self.award_remaining_sets(team.opponent());
+                self.status = MatchStatus::Finished;
+                self.swapped = false;
+                self.side_switch_due = false;
+                self.timeout = None;
+                self.outcome = Some(if event.kind == EventKind::Forfeit {
+                    Outcome::Forfeit(team)
+                } else {
+                    Outcome::Retirement(team)
+                });
+            }
+            EventKind::Undo => {}
+        }
+    }
+
+    /// Default result: the current set and every set not played go to the winner
+    fn award_remaining_sets(&mut self, winner: Team) {
+        loop {
+            let points = self
+                .rules
+                .target(&self.result)
+                .max(self.set_points(winner.opponent()) + self.rules.min_lead);
+            let set_points = self.set_results_mut(winner).last_mut().unwrap();
+            *set_points = (*set_points).max(points);
+            self.result[winner.index()] += 1;
+            if self.result[winner.index()] >= self.rules.sets_to_win {
+                break;
+            }
+            self.set_results_a.push(0);
+            self.set_results_b.push(0);
+        }
+    }
+
+    pub fn set_results(&self, team: Team) -> &Vec<i32> {
+        match team {
+            Team::A => &self.set_results_a,
+            Team::B => &self.set_results_b,
+        }
+    }
+
+    fn set_results_mut(&mut self, team: Team) -> &mut Vec<i32> {
+        match team {
+            Team::A => &mut self.set_results_a,
+            Team::B => &mut self.set_results_b,
+        }
+    }
+
+    pub fn set_points(&self, team: Team) -> i32 {
+        *self.set_results(team).last().unwrap()
+    }
+
+    /// Starting position of the player serving now, or next time the team wins the serve
+    pub fn server_position(&self, team: Team) -> i32 {
+        let rotation = self.rotation[team.index()];
+        if self.serving == Some(team) {
+            rotation + 1
+        } else {
+            (rotation + 1) % 6 + 1
+        }
+    }
+
+    pub fn timeouts_left(&self, team: Team) -> i32 {
+        self.rules.timeouts_per_set - self.timeouts[team.index()]
+    }
+
+    pub fn set_substitutions(&self, team: Team) -> usize {
+        self.substitutions
+            .iter()
+            .filter(|substitution| {
+                substitution.team == team && substitution.set == self.set_results_a.len()
+            })
+            .count()
+    }
+
+    pub fn running_timeout(&self) -> Option<&Timeout> {
+        self.timeout.as_ref().filter(|timeout| timeout.remaining() > 0)
+    }
+
+    /// Events after the match start, latest first; resuming a suspended match is not a boundary
+    fn undoable_events(&self) -> impl Iterator<Item = &MatchEvent> {
+        let start = self
+            .history
+            .iter()
+            .position(|event| {
+                event.kind == EventKind::Status && event.status == Some(MatchStatus::InProgress)
+            })
+            .map_or(0, |index| index + 1);
+        self.history[start..].iter().rev()
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.undoable_events().next().is_some()
+    }
+
+    /// Points won by the team with the given tag, `None` counts untagged points
+    pub fn rally_points(&self, team: Team, rally_type: Option<RallyType>) -> usize {
+        self.history
+            .iter()
+            .filter(|event| {
+                event.kind == EventKind::Point
+                    && event.team == Some(team)
+                    && event.rally_type == rally_type
+            })
+            .count()
+    }
+
+    /// Points given to the team by a forfeit or retirement rather than won in a rally
+    pub fn awarded_points(&self, team: Team) -> usize {
+        let rallies = self
+            .history
+            .iter()
+            .filter(|event| event.kind == EventKind::Point && event.team == Some(team))
+            .count();
+        self.set_results(team).iter().sum::<i32>() as usize - rallies
+    }
+
+    pub fn roster_player(&self, team: Team, player_id: i32) -> Option<&Player> {
+        self.rosters[team.index()]
+            .iter()
+            .find(|player| player.id == player_id)
+    }
+
+    pub fn team_id(&self, team: Team) -> i32 {
+        match team {
+            Team::A => self.team_a_id,
+            Team::B => self.team_b_id,
+        }
+    }
+
+    pub fn team_name(&self, team: Team) -> &str {
+        match team {
+            Team::A => &self.team_a,
+            Team::B => &self.team_b,
+        }
+    }
+
+    pub fn official_ids(&self) -> Vec<i32> {
+        self.officials
+            .iter()
+            .map(|official| official.official_id)
+            .collect()
+    }
+
+    pub fn player_point_events(&self, player_id: i32) -> impl Iterator<Item = &MatchEvent> {
+        self.history
+            .iter()
+            .filter(move |event| event.kind == EventKind::Point && event.player_id == Some(player_id))
+    }
+
+    /// Penalty which awarded the point, a penalty point goes together with its sanction
+    fn penalty_of(&self, point: &MatchEvent) -> Option<&MatchEvent> {
+        let index = self.history.iter().position(|event| event.id == point.id)?;
+        self.history[..index].last().filter(|event| {
+            event.kind == EventKind::Sanction
+                && event.sanction == Some(Sanction::Penalty)
+                && event.team == point.team.map(Team::opponent)
+        })
+    }
+
+    fn last_point(&self, team: Team) -> Option<&MatchEvent> {
+        self.history
+            .iter()
+            .rev()
+            .take_while(|event| event.kind != EventKind::SetEnd)
+            .find(|event| event.kind == EventKind::Point && event.team == Some(team))
+    }
 }
 
 pub async fn match_exists(conn: &mut PgConnection, match_id: i32) -> bool {
@@ -35,36 +634,173 @@ pub async fn match_exists(conn: &mut PgConnection, match_id: i32) -> bool {
         > 0
 }
 
-pub async fn get_matches(conn: &mut PgConnection) -> Vec<Match> {
-    query_as!(
-        Match,
-        r#"SELECT id, match_start, set_start, team_a, team_b, swapped, result, set_results_a, set_results_b, status "status: MatchStatus" FROM matches"#
+pub async fn get_matches(conn: &mut PgConnection, season_id: i32) -> Vec<Match> {
+    let rows = query_as!(
+        MatchRow,
+        r#"SELECT m.id, match_start, a.name team_a, b.name team_b, team_a_id, team_b_id, competition_id, court_id, v.name || ' ' || c.name "court?", season_id, season_id <> current_season() "archived!", rules "rules: ScoringRules" FROM matches m JOIN teams a ON a.id=team_a_id JOIN teams b ON b.id=team_b_id LEFT JOIN courts c ON c.id=court_id LEFT JOIN venues v ON v.id=c.venue_id WHERE season_id=$1 ORDER BY m.id"#,
+        season_id
     )
-    .fetch_all(conn)
+    .fetch_all(conn.as_mut())
+    .await
+    .unwrap();
+    let mut events: HashMap<i32, Vec<MatchEvent>> = HashMap::new();
+    for event in query_as!(
+        MatchEvent,
+        r#"SELECT id, match_id, created_at, kind "kind: EventKind", team "team: Team", status "status: MatchStatus", reverts, player_in, player_out, sanction "sanction: Sanction", player, scheduled_at, rally_type "rally_type: RallyType", player_id FROM match_events ORDER BY id"#
+    )
+    .fetch_all(conn.as_mut())
+    .await
+    .unwrap()
+    {
+        events.entry(event.match_id).or_default().push(event);
+    }
+    let mut players: HashMap<i32, Vec<Player>> = HashMap::new();
+    for player in query_as!(
+        Player,
+        r#"SELECT p.id, team_id, t.name team, number, p.name, role "role: PlayerRole" FROM players p JOIN teams t ON t.id=team_id ORDER BY number"#
+    )
+    .fetch_all(conn.as_mut())
+    .await
+    .unwrap()
+    {
+        players.entry(player.team_id).or_default().push(player);
+    }
+    let mut officials: HashMap<i32, Vec<MatchOfficial>> = HashMap::new();
+    for official in query_as!(
+        MatchOfficial,
+        r#"SELECT match_id, official_id, o.name, role "role: OfficialRole" FROM match_officials JOIN officials o ON o.id=official_id ORDER BY role, o.name"#
+    )
+    .fetch_all(conn.as_mut())
     .await
     .unwrap()
+    {
+        officials.entry(official.match_id).or_default().push(official);
+    }
+    rows.into_iter()
+        .map(|row| {
+            let match_events = events.remove(&row.id).unwrap_or_default();
+            let mut match_info = Match::project(row, match_events);
+            match_info.rosters = [
+                players.get(&match_info.team_a_id).cloned().unwrap_or_default(),
+                players.get(&match_info.team_b_id).cloned().unwrap_or_default(),
+            ];
+            match_info.officials = officials.remove(&match_info.id).unwrap_or_default();
+            match_info
+        })
+        .collect()
 }
 
 pub async fn get_match(conn: &mut PgConnection, match_id: i32) -> Match {
+    let row = query_as!(
+        MatchRow,
+        r#"SELECT m.id, match_start, a.name team_a, b.name team_b, team_a_id, team_b_id, competition_id, court_id, v.name || ' ' || c.name "court?", season_id, season_id <> current_season() "archived!", rules "rules: ScoringRules" FROM matches m JOIN teams a ON a.id=team_a_id JOIN teams b ON b.id=team_b_id LEFT JOIN courts c ON c.id=court_id LEFT JOIN venues v ON v.id=c.venue_id WHERE m.id=$1"#,
+        match_id
+    )
+    .fetch_one(conn.as_mut())
+    .await
+    .unwrap();
+    let events = query_as!(
+        MatchEvent,
+        r#"SELECT id, match_id, created_at, kind "kind: EventKind", team "team: Team", status "status: MatchStatus", reverts, player_in, player_out, sanction "sanction: Sanction", player, scheduled_at, rally_type "rally_type: RallyType", player_id FROM match_events WHERE match_id=$1 ORDER BY id"#,
+        match_id
+    )
+    .fetch_all(conn.as_mut())
+    .await
+    .unwrap();
+    let mut match_info = Match::project(row, events);
+    match_info.rosters = [
+        get_roster(conn, match_info.team_a_id).await,
+        get_roster(conn, match_info.team_b_id).await,
+    ];
+    match_info.officials = query_as!(
+        MatchOfficial,
+        r#"SELECT match_id, official_id, o.name, role "role: OfficialRole" FROM match_officials JOIN officials o ON o.id=official_id WHERE match_id=$1 ORDER BY role, o.name"#,
+        match_id
+    )
+    .fetch_all(conn)
+    .await
+    .unwrap();
+    match_info
+}
+
+pub async fn get_roster(conn: &mut PgConnection, team_id: i32) -> Vec<Player> {
     query_as!(
-        Match,
-        r#"SELECT id, match_start, set_start, team_a, team_b, swapped, result, set_results_a, set_results_b, status "status: MatchStatus" FROM matches WHERE id=$1"#,match_id
+        Player,
+        r#"SELECT p.id, team_id, t.name team, number, p.name, role "role: PlayerRole" FROM players p JOIN teams t ON t.id=team_id WHERE team_id=$1 ORDER BY number"#,
+        team_id
     )
-    .fetch_one(conn)
+    .fetch_all(conn)
     .await
     .unwrap()
 }
 
-pub async fn swap_teams(conn: &mut PgConnection, match_id: i32) -> bool {
+/// Locks a match for a change, matches of archived seasons are read-only
+async fn lock_match(conn: &mut PgConnection, match_id: i32) -> Option<Match> {
     query!(
-        "UPDATE matches SET swapped=not swapped WHERE id=$1 AND status='IN_PROGRESS'",
+        "SELECT id FROM matches WHERE id=$1 AND season_id=current_season() FOR UPDATE",
         match_id
+    )
+        .fetch_optional(conn.as_mut())
+        .await
+        .unwrap()?;
+    Some(get_match(conn, match_id).await)
+}
+
+async fn push_event(
+    conn: &mut PgConnection,
+    match_id: i32,
+    kind: EventKind,
+    event: NewEvent,
+) -> Match {
+    query!(
+        "INSERT INTO match_events(match_id, kind, team, status, reverts, player_in, player_out, sanction, player, scheduled_at, rally_type, player_id) VALUES($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)",
+        match_id,
+        kind as EventKind,
+        event.team as Option<Team>,
+        event.status as Option<MatchStatus>,
+        event.reverts,
+        event.player_in,
+        event.player_out,
+        event.sanction as Option<Sanction>,
+        event.player,
+        event.scheduled_at,
+        event.rally_type as Option<RallyType>,
+        event.player_id
+    )
+    .execute(conn.as_mut())
+    .await
+    .unwrap();
+    store_projection(conn, match_id).await
+}
+
+async fn store_projection(conn: &mut PgConnection, match_id: i32) -> Match {
+    let match_info = get_match(conn, match_id).await;
+    query!(
+        "UPDATE matches SET match_start=$2, set_start=$3, swapped=$4, result=$5, set_results_a=$6, set_results_b=$7, status=$8 WHERE id=$1",
+        match_id,
+        match_info.match_start,
+        match_info.set_start,
+        match_info.swapped,
+        &match_info.result,
+        &match_info.set_results_a,
+        &match_info.set_results_b,
+        match_info.status as MatchStatus
     )
     .execute(conn)
     .await
-    .unwrap()
-    .rows_affected()
-        > 0
+    .unwrap();
+    match_info
+}
+
+pub async fn swap_teams(conn: &mut PgConnection, match_id: i32) -> bool {
+    let Some(match_info) = lock_match(conn, match_id).await else {
+        return false;
+    };
+    if match_info.status != MatchStatus::InProgress {
+        return false;
+    }
+    push_event(conn, match_id, EventKind::Swap, NewEvent::default()).await;
+    true
 }
 
 #[derive(Debug)]
@@ -74,6 +810,12 @@ pub enum MatchAddError {
     DuplicateTeamName,
     PastDate,
     IncorrectDateFormat,
+    UnknownRuleset,
+    UnknownCompetition,
+    UnknownCourt,
+    CourtDoubleBooked,
+    TeamDoubleBooked,
+    OfficialDoubleBooked,
 }
 
 impl Display for MatchAddError {
@@ -84,6 +826,12 @@ impl Display for MatchAddError {
                 MatchAddError::PastDate => "Past dates are not allowed",
                 MatchAddError::IncorrectDateFormat => "Incorrect date format. Date has to be a valid ISO8601 timestamp. Leave empty to use current time",
                 MatchAddError::TeamNameTooLong => "Team name can't be longer than 50 characters",
+                MatchAddError::UnknownRuleset => "Unknown ruleset",
+                MatchAddError::UnknownCompetition => "Unknown competition",
+                MatchAddError::UnknownCourt => "Unknown court",
+                MatchAddError::CourtDoubleBooked => "The court is already booked for another match at this time",
+                MatchAddError::TeamDoubleBooked => "One of the teams already plays another match at this time",
+                MatchAddError::OfficialDoubleBooked => "One of the officials is already assigned to another match at this time",
             }
         )
     }
@@ -96,154 +844,2534 @@ pub async fn add_match(
     team_a_name: &str,
     team_b_name: &str,
     match_date: &str,
+    ruleset: &str,
+    competition: &str,
+    court: &str,
 ) -> Result<Match, MatchAddError> {
-    let team_a_name = team_a_name.trim();
-    let team_b_name = team_b_name.trim();
+    let team_a_name = team_a_name.split_whitespace().collect::<Vec<_>>().join(" ");
+    let team_b_name = team_b_name.split_whitespace().collect::<Vec<_>>().join(" ");
     if team_a_name.is_empty() || team_b_name.is_empty() {
         return Err(MatchAddError::TeamNameEmpty);
     }
     if team_a_name.chars().count() > 50 || team_b_name.chars().count() > 50 {
         return Err(MatchAddError::TeamNameTooLong);
     }
-    if team_a_name == team_b_name {
+    if team_key(&team_a_name) == team_key(&team_b_name) {
         return Err(MatchAddError::DuplicateTeamName);
     }
-    if match_date.trim().is_empty() {
-        Ok(query_as!(
-                    Match,
-                    r#"INSERT INTO matches(team_a, team_b) VALUES($1, $2) RETURNING id, match_start, set_start, team_a, team_b, swapped, result, set_results_a, set_results_b, status "status: MatchStatus""#,
-                    team_a_name,
-                    team_b_name
-                )
-                .fetch_one(conn)
-                .await.unwrap())
-    } else if let Ok(date) = PrimitiveDateTime::parse(&match_date, &Iso8601::DEFAULT) {
-        if date.assume_utc() > OffsetDateTime::now_utc() {
-            Ok(query_as!(
-                            Match,
-                            r#"INSERT INTO matches(status, match_start, set_start, team_a, team_b) VALUES('PLANNED', $1, $1, $2, $3) RETURNING id, match_start, set_start, team_a, team_b, swapped, result, set_results_a, set_results_b, status "status: MatchStatus""#,
-                            date,
-                            team_a_name,
-                            team_b_name
-                        )
-                        .fetch_one(conn)
-                        .await.unwrap())
-        } else {
-            Err(MatchAddError::PastDate)
-        }
+    let Some(rules) = ScoringRules::preset(ruleset) else {
+        return Err(MatchAddError::UnknownRuleset);
+    };
+    let date = if match_date.trim().is_empty() {
+        None
     } else {
-        Err(MatchAddError::IncorrectDateFormat)
-    }
-}
-
-pub async fn remove_match(conn: &mut PgConnection, match_id: i32) -> bool {
-    query!("DELETE FROM matches WHERE id = $1", match_id)
-        .execute(conn)
+        Some(parse_future_date(match_date)?)
+    };
+    let competition_id = parse_competition(conn, competition).await?;
+    let court_id = parse_court(conn, court).await?;
+    // Teams are created in key order like in `lock_teams`, a concurrent insert of the same key waits
+    let (team_a_id, team_b_id) = if team_key(&team_a_name) < team_key(&team_b_name) {
+        let team_a_id = find_or_create_team(conn, &team_a_name).await;
+        (team_a_id, find_or_create_team(conn, &team_b_name).await)
+    } else {
+        let team_b_id = find_or_create_team(conn, &team_b_name).await;
+        (find_or_create_team(conn, &team_a_name).await, team_b_id)
+    };
+    let now = OffsetDateTime::now_utc();
+    check_bookings(
+        conn,
+        None,
+        [team_a_id, team_b_id],
+        court_id,
+        &[],
+        date.unwrap_or(PrimitiveDateTime::new(now.date(), now.time())),
+        rules,
+    )
+    .await?;
+    if let Some(date) = date {
+        let match_id = query!(
+            "INSERT INTO matches(match_start, set_start, team_a_id, team_b_id, rules, competition_id, court_id) VALUES($1, $1, $2, $3, $4, $5, $6) RETURNING id",
+            date,
+            team_a_id,
+            team_b_id,
+            rules as ScoringRules,
+            competition_id,
+            court_id
+        )
+        .fetch_one(conn.as_mut())
         .await
         .unwrap()
-        .rows_affected()
-        > 0
-}
-
-pub async fn add_set_point_a(conn: &mut PgConnection, match_id: i32) -> bool {
-    query!(
-        "UPDATE matches SET set_results_a[array_length(set_results_a,1)]=set_results_a[array_length(set_results_a,1)]+1 WHERE id=$1 AND status='IN_PROGRESS' AND set_results_a[array_length(set_results_a,1)]<2147483647",
-        match_id
-    )
-    .execute(conn)
-    .await
-    .unwrap().rows_affected() > 0
+        .id;
+        Ok(get_match(conn, match_id).await)
+    } else {
+        let match_id = query!(
+            "INSERT INTO matches(team_a_id, team_b_id, rules, competition_id, court_id) VALUES($1, $2, $3, $4, $5) RETURNING id",
+            team_a_id,
+            team_b_id,
+            rules as ScoringRules,
+            competition_id,
+            court_id
+        )
+        .fetch_one(conn.as_mut())
+        .await
+        .unwrap()
+        .id;
+        push_event(
+            conn,
+            match_id,
+            EventKind::Status,
+            NewEvent {
+                status: Some(MatchStatus::InProgress),
+                ..Default::default()
+            },
+        )
+        .await;
+        Ok(get_match(conn, match_id).await)
+    }
 }
 
-pub async fn add_set_point_b(conn: &mut PgConnection, match_id: i32) -> bool {
+pub async fn parse_competition(
+    conn: &mut PgConnection,
+    competition: &str,
+) -> Result<Option<i32>, MatchAddError> {
+    if competition.trim().is_empty() {
+        return Ok(None);
+    }
+    let Ok(competition_id) = competition.trim().parse::<i32>() else {
+        return Err(MatchAddError::UnknownCompetition);
+    };
     query!(
-        "UPDATE matches SET set_results_b[array_length(set_results_b,1)]=set_results_b[array_length(set_results_b,1)]+1 WHERE id=$1 AND status='IN_PROGRESS' AND set_results_b[array_length(set_results_b,1)]<2147483647",
-        match_id
+        "SELECT id FROM competitions WHERE id=$1 AND season_id=current_season()",
+        competition_id
     )
-    .execute(conn)
+    .fetch_optional(conn)
     .await
-    .unwrap().rows_affected() > 0
+    .unwrap()
+    .map(|row| Some(row.id))
+    .ok_or(MatchAddError::UnknownCompetition)
 }
 
-pub async fn remove_set_point_a(conn: &mut PgConnection, match_id: i32) -> bool {
-    query!(
-        "UPDATE matches SET set_results_a[array_length(set_results_a,1)]=set_results_a[array_length(set_results_a,1)]-1 WHERE id=$1 AND status='IN_PROGRESS' AND set_results_a[array_length(set_results_a,1)]>0",
-        match_id
-    )
-    .execute(conn)
-    .await
-    .unwrap().rows_affected() > 0
+async fn parse_court(conn: &mut PgConnection, court: &str) -> Result<Option<i32>, MatchAddError> {
+    if court.trim().is_empty() {
+        return Ok(None);
+    }
+    let Ok(court_id) = court.trim().parse::<i32>() else {
+        return Err(MatchAddError::UnknownCourt);
+    };
+    query!("SELECT id FROM courts WHERE id=$1", court_id)
+        .fetch_optional(conn)
+        .await
+        .unwrap()
+        .map(|row| Some(row.id))
+        .ok_or(MatchAddError::UnknownCourt)
 }
 
-pub async fn remove_set_point_b(conn: &mut PgConnection, match_id: i32) -> bool {
-    query!(
-        "UPDATE matches SET set_results_b[array_length(set_results_b,1)]=set_results_b[array_length(set_results_b,1)]-1 WHERE id=$1 AND status='IN_PROGRESS' AND set_results_b[array_length(set_results_b,1)]>0",
-        match_id
+/// Rejects a slot overlapping another active match on the same court, of one of the teams or of one of the officials,
+/// a match occupies its court for the slot length of the venue, a match without a court for the expected length of its ruleset
+async fn check_bookings(
+    conn: &mut PgConnection,
+    match_id: Option<i32>,
+    team_ids: [i32; 2],
+    court_id: Option<i32>,
+    official_ids: &[i32],
+    start: PrimitiveDateTime,
+    rules: ScoringRules,
+) -> Result<(), MatchAddError> {
+    lock_bookings(conn, &team_ids, court_id, official_ids).await;
+    let slot_minutes = match court_id {
+        Some(court_id) => {
+            query!(
+                "SELECT slot_minutes FROM courts c JOIN venues v ON v.id=venue_id WHERE c.id=$1",
+                court_id
+            )
+            .fetch_one(conn.as_mut())
+            .await
+            .unwrap()
+            .slot_minutes
+        }
+        None => rules.expected_minutes(),
+    };
+    let clashes = query!(
+        r#"SELECT m.court_id IS NOT DISTINCT FROM $5 AND $5 IS NOT NULL "same_court!", (m.team_a_id = ANY($4) OR m.team_b_id = ANY($4)) "same_team!" FROM matches m LEFT JOIN courts c ON c.id=m.court_id LEFT JOIN venues v ON v.id=c.venue_id
+        WHERE m.id IS DISTINCT FROM $1 AND m.status IN ('PLANNED', 'IN_PROGRESS', 'SUSPENDED')
+        AND m.match_start < $2::TIMESTAMP + make_interval(mins => $3) AND $2 < m.match_start + make_interval(mins => COALESCE(v.slot_minutes, (m.rules).sets_to_win * $7))
+        AND (m.court_id = $5 OR m.team_a_id = ANY($4) OR m.team_b_id = ANY($4)
+            OR EXISTS(SELECT 1 FROM match_officials mo WHERE mo.match_id=m.id AND mo.official_id = ANY($6)))"#,
+        match_id,
+        start,
+        slot_minutes,
+        &team_ids[..],
+        court_id,
+        official_ids,
+        SET_MINUTES
     )
-    .execute(conn)
+    .fetch_all(conn)
     .await
-    .unwrap().rows_affected() > 0
+    .unwrap();
+    if clashes.iter().any(|clash| clash.same_court) {
+        return Err(MatchAddError::CourtDoubleBooked);
+    }
+    if clashes.iter().any(|clash| clash.same_team) {
+        return Err(MatchAddError::TeamDoubleBooked);
+    }
+    if !clashes.is_empty() {
+        return Err(MatchAddError::OfficialDoubleBooked);
+    }
+    Ok(())
 }
 
-pub async fn end_set(conn: &mut PgConnection, match_id: i32) -> bool {
-    let result=query!(r#"SELECT status "status: MatchStatus", result,set_results_a[array_length(set_results_a,1)] "set_points_a!",set_results_b[array_length(set_results_b,1)] "set_points_b!" FROM matches WHERE id=$1"#,match_id).fetch_one(conn.as_mut()).await.unwrap();
-    if result.status == MatchStatus::Finished {
-        return false;
-    }
-    if result.status == MatchStatus::Planned {
+/// Serializes booking checks of the same court, team or official until the transaction ends.
+/// One call takes its locks in a fixed order, but a transaction booking several matches takes
+/// the locks of each match in turn and has to hold all of them up front, see `lock_teams`
+async fn lock_bookings(
+    conn: &mut PgConnection,
+    team_ids: &[i32],
+    court_id: Option<i32>,
+    official_ids: &[i32],
+) {
+    let mut keys: Vec<(i32, i32)> = court_id
+        .map(|court_id| (1, court_id))
+        .into_iter()
+        .chain(team_ids.iter().map(|&team_id| (2, team_id)))
+        .chain(official_ids.iter().map(|&official_id| (3, official_id)))
+        .collect();
+    keys.sort();
+    keys.dedup();
+    for (class, id) in keys {
         query!(
-            "UPDATE matches SET status='IN_PROGRESS',match_start=now(),set_start=now() WHERE id=$1",
-            match_id
+            r#"SELECT 1 "locked!" FROM pg_advisory_xact_lock($1, $2)"#,
+            class,
+            id
         )
-        .execute(conn.as_mut())
+        .fetch_one(conn.as_mut())
         .await
         .unwrap();
-        return true;
-    }
-    if result.result[0] == 2 && result.result[1] == 2 {
-        if (result.set_points_a < 15 && result.set_points_b < 15)
-            || result.set_points_a.abs_diff(result.set_points_b) < 2
-        {
-            return false;
-        }
-    } else {
-        if (result.set_points_a < 25 && result.set_points_b < 25)
-            || result.set_points_a.abs_diff(result.set_points_b) < 2
-        {
-            return false;
-        }
     }
-    let result_index = if result.set_points_a > result.set_points_b {
-        0
-    } else {
-        1
-    };
-    if result.result[result_index] >= 2 {
-        query!(
-            "UPDATE matches SET swapped=FALSE, status='FINISHED' WHERE id=$1",
-            match_id
+}
+
+const FOLD_FROM: &str = "ąćęłńóśźżáàâäãåéèêëíìîïòôöõúùûüýÿçñšžčřďťň";
+const FOLD_TO: &str = "acelnoszzaaaaaaeeeeiiiioooouuuuyycnszcrdtn";
+
+/// Lookup key under which differently typed names of one team match,
+/// kept in sync with the `team_key` function of the teams migration
+pub fn team_key(team_name: &str) -> String {
+    team_name
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+        .chars()
+        .map(|c| {
+            FOLD_FROM
+                .chars()
+                .zip(FOLD_TO.chars())
+                .find_map(|(from, to)| (from == c).then_some(to))
+                .unwrap_or(c)
+        })
+        .collect()
+}
+
+pub struct TeamInfo {
+    pub id: i32,
+    pub name: String,
+}
+
+pub async fn find_team(conn: &mut PgConnection, team_name: &str) -> Option<TeamInfo> {
+    query_as!(
+        TeamInfo,
+        "SELECT id, name FROM teams WHERE key=$1",
+        team_key(team_name)
+    )
+    .fetch_optional(conn)
+    .await
+    .unwrap()
+}
+
+pub async fn get_team(conn: &mut PgConnection, team_id: i32) -> Option<TeamInfo> {
+    query_as!(TeamInfo, "SELECT id, name FROM teams WHERE id=$1", team_id)
+        .fetch_optional(conn)
+        .await
+        .unwrap()
+}
+
+/// Creates the teams of a batch of matches and holds their bookings until the transaction ends,
+/// so that two batches sharing teams cannot deadlock while booking their matches one by one
+pub async fn lock_teams(conn: &mut PgConnection, team_names: &[&str]) {
+    let mut teams: Vec<(String, String)> = team_names
+        .iter()
+        .map(|name| {
+            let name = name.split_whitespace().collect::<Vec<_>>().join(" ");
+            (team_key(&name), name)
+        })
+        .collect();
+    teams.sort();
+    teams.dedup_by(|a, b| a.0 == b.0);
+    let mut team_ids = Vec::new();
+    for (_, name) in &teams {
+        team_ids.push(find_or_create_team(conn, name).await);
+    }
+    lock_bookings(conn, &team_ids, None, &[]).await;
+}
+
+async fn find_or_create_team(conn: &mut PgConnection, team_name: &str) -> i32 {
+    let key = team_key(team_name);
+    query!(
+        "INSERT INTO teams(name, key) VALUES($1, $2) ON CONFLICT (key) DO NOTHING",
+        team_name,
+        key
+    )
+    .execute(conn.as_mut())
+    .await
+    .unwrap();
+    query!("SELECT id FROM teams WHERE key=$1", key)
+        .fetch_one(conn)
+        .await
+        .unwrap()
+        .id
+}
+
+pub async fn get_team_names(conn: &mut PgConnection) -> Vec<String> {
+    query!("SELECT name FROM teams ORDER BY name")
+        .fetch_all(conn)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|row| row.name)
+        .collect()
+}
+
+pub fn parse_future_date(match_date: &str) -> Result<PrimitiveDateTime, MatchAddError> {
+    let Ok(date) = PrimitiveDateTime::parse(match_date.trim(), &Iso8601::DEFAULT) else {
+        return Err(MatchAddError::IncorrectDateFormat);
+    };
+    if date.assume_utc() > OffsetDateTime::now_utc() {
+        Ok(date)
+    } else {
+        Err(MatchAddError::PastDate)
+    }
+}
+
+pub async fn remove_match(conn: &mut PgConnection, match_id: i32) -> bool {
+    query!(
+        "DELETE FROM matches WHERE id = $1 AND season_id = current_season() AND NOT EXISTS(SELECT 1 FROM bracket_nodes WHERE match_id = $1)",
+        match_id
+    )
+        .execute(conn)
+        .await
+        .unwrap()
+        .rows_affected()
+        > 0
+}
+
+pub async fn add_set_point(
+    conn: &mut PgConnection,
+    match_id: i32,
+    team: Team,
+    rally_type: Option<RallyType>,
+    player_id: Option<i32>,
+) -> bool {
+    let Some(match_info) = lock_match(conn, match_id).await else {
+        return false;
+    };
+    if match_info.status != MatchStatus::InProgress || match_info.set_points(team) == i32::MAX {
+        return false;
+    }
+    if let Some(player_id) = player_id {
+        let Some(player) = match_info.roster_player(team, player_id) else {
+            return false;
+        };
+        // A libero may neither serve nor block
+        if player.role == PlayerRole::Libero
+            && matches!(rally_type, Some(RallyType::Ace | RallyType::Block))
+        {
+            return false;
+        }
+    }
+    let match_info = push_event(
+        conn,
+        match_id,
+        EventKind::Point,
+        NewEvent {
+            team: Some(team),
+            rally_type,
+            player_id,
+            ..Default::default()
+        },
+    )
+    .await;
+    if !match_info.technical_timeout_taken
+        && match_info.rules.technical_timeout_due(
+            &match_info.result,
+            match_info.set_points(Team::A),
+            match_info.set_points(Team::B),
+        )
+    {
+        push_event(
+            conn,
+            match_id,
+            EventKind::TechnicalTimeout,
+            NewEvent::default(),
+        )
+        .await;
+    }
+    true
+}
+
+pub async fn take_timeout(conn: &mut PgConnection, match_id: i32, team: Team) -> bool {
+    let Some(match_info) = lock_match(conn, match_id).await else {
+        return false;
+    };
+    if match_info.status != MatchStatus::InProgress
+        || match_info.timeouts_left(team) <= 0
+        || match_info.running_timeout().is_some()
+    {
+        return false;
+    }
+    push_event(
+        conn,
+        match_id,
+        EventKind::Timeout,
+        NewEvent {
+            team: Some(team),
+            ..Default::default()
+        },
+    )
+    .await;
+    true
+}
+
+pub async fn remove_set_point(conn: &mut PgConnection, match_id: i32, team: Team) -> bool {
+    let Some(match_info) = lock_match(conn, match_id).await else {
+        return false;
+    };
+    if match_info.status != MatchStatus::InProgress {
+        return false;
+    }
+    let Some(point) = match_info.last_point(team) else {
+        return false;
+    };
+    for event in [Some(point), match_info.penalty_of(point)].into_iter().flatten() {
+        push_event(
+            conn,
+            match_id,
+            EventKind::Undo,
+            NewEvent {
+                team: event.team,
+                reverts: Some(event.id),
+                ..Default::default()
+            },
+        )
+        .await;
+    }
+    true
+}
+
+pub async fn start_match(conn: &mut PgConnection, match_id: i32, first_server: Team) -> bool {
+    let Some(match_info) = lock_match(conn, match_id).await else {
+        return false;
+    };
+    if match_info.status != MatchStatus::Planned {
+        return false;
+    }
+    push_event(
+        conn,
+        match_id,
+        EventKind::Status,
+        NewEvent {
+            team: Some(first_server),
+            status: Some(MatchStatus::InProgress),
+            ..Default::default()
+        },
+    )
+    .await;
+    true
+}
+
+pub async fn forfeit(conn: &mut PgConnection, match_id: i32, team: Team) -> bool {
+    let Some(match_info) = lock_match(conn, match_id).await else {
+        return false;
+    };
+    if matches!(
+        match_info.status,
+        MatchStatus::Finished | MatchStatus::Cancelled
+    ) {
+        return false;
+    }
+    let match_info = push_event(
+        conn,
+        match_id,
+        EventKind::Forfeit,
+        NewEvent {
+            team: Some(team),
+            ..Default::default()
+        },
+    )
+    .await;
+    advance_bracket(conn, &match_info).await;
+    true
+}
+
+pub async fn retire(conn: &mut PgConnection, match_id: i32, team: Team) -> bool {
+    let Some(match_info) = lock_match(conn, match_id).await else {
+        return false;
+    };
+    if match_info.status != MatchStatus::InProgress {
+        return false;
+    }
+    let match_info = push_event(
+        conn,
+        match_id,
+        EventKind::Retirement,
+        NewEvent {
+            team: Some(team),
+            ..Default::default()
+        },
+    )
+    .await;
+    advance_bracket(conn, &match_info).await;
+    true
+}
+
+pub async fn change_status(conn: &mut PgConnection, match_id: i32, status: MatchStatus) -> bool {
+    let Some(match_info) = lock_match(conn, match_id).await else {
+        return false;
+    };
+    if !match_info.status.can_change_to(status) {
+        return false;
+    }
+    if status == MatchStatus::Cancelled && in_bracket(conn, match_id).await {
+        return false;
+    }
+    push_event(
+        conn,
+        match_id,
+        EventKind::Status,
+        NewEvent {
+            status: Some(status),
+            ..Default::default()
+        },
+    )
+    .await;
+    true
+}
+
+#[derive(Debug)]
+pub enum RescheduleError {
+    NotPostponed,
+    Date(MatchAddError),
+}
+
+impl Display for RescheduleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RescheduleError::NotPostponed => f.write_str("Only postponed matches can be rescheduled"),
+            RescheduleError::Date(e) => e.fmt(f),
+        }
+    }
+}
+
+impl Error for RescheduleError {}
+
+pub async fn reschedule(
+    conn: &mut PgConnection,
+    match_id: i32,
+    match_date: &str,
+) -> Result<(), RescheduleError> {
+    let Some(match_info) = lock_match(conn, match_id).await else {
+        return Err(RescheduleError::NotPostponed);
+    };
+    if match_info.status != MatchStatus::Postponed {
+        return Err(RescheduleError::NotPostponed);
+    }
+    let date = parse_future_date(match_date).map_err(RescheduleError::Date)?;
+    check_bookings(
+        conn,
+        Some(match_id),
+        [match_info.team_a_id, match_info.team_b_id],
+        match_info.court_id,
+        &match_info.official_ids(),
+        date,
+        match_info.rules,
+    )
+    .await
+    .map_err(RescheduleError::Date)?;
+    push_event(
+        conn,
+        match_id,
+        EventKind::Status,
+        NewEvent {
+            status: Some(MatchStatus::Planned),
+            scheduled_at: Some(date),
+            ..Default::default()
+        },
+    )
+    .await;
+    Ok(())
+}
+
+#[derive(Debug)]
+pub enum CourtAssignError {
+    NotPlanned,
+    Court(MatchAddError),
+}
+
+impl Display for CourtAssignError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CourtAssignError::NotPlanned => {
+                f.write_str("Only planned matches can be assigned to a court")
+            }
+            CourtAssignError::Court(e) => e.fmt(f),
+        }
+    }
+}
+
+impl Error for CourtAssignError {}
+
+pub async fn assign_court(
+    conn: &mut PgConnection,
+    match_id: i32,
+    court: &str,
+) -> Result<(), CourtAssignError> {
+    let Some(match_info) = lock_match(conn, match_id).await else {
+        return Err(CourtAssignError::NotPlanned);
+    };
+    if match_info.status != MatchStatus::Planned {
+        return Err(CourtAssignError::NotPlanned);
+    }
+    let court_id = parse_court(conn, court)
+        .await
+        .map_err(CourtAssignError::Court)?;
+    check_bookings(
+        conn,
+        Some(match_id),
+        [match_info.team_a_id, match_info.team_b_id],
+        court_id,
+        &match_info.official_ids(),
+        match_info.match_start,
+        match_info.rules,
+    )
+    .await
+    .map_err(CourtAssignError::Court)?;
+    query!(
+        "UPDATE matches SET court_id=$2 WHERE id=$1",
+        match_id,
+        court_id
+    )
+    .execute(conn)
+    .await
+    .unwrap();
+    Ok(())
+}
+
+pub async fn end_set(conn: &mut PgConnection, match_id: i32) -> bool {
+    let Some(match_info) = lock_match(conn, match_id).await else {
+        return false;
+    };
+    match match_info.status {
+        MatchStatus::InProgress => {
+            let outcome = match_info.rules.set_outcome(
+                &match_info.result,
+                match_info.set_points(Team::A),
+                match_info.set_points(Team::B),
+            );
+            let result_index = match outcome {
+                SetOutcome::InProgress => return false,
+                SetOutcome::SetWon(result_index) | SetOutcome::MatchWon(result_index) => {
+                    result_index
+                }
+            };
+            push_event(
+                conn,
+                match_id,
+                EventKind::SetEnd,
+                NewEvent {
+                    team: Some(Team::from_index(result_index)),
+                    ..Default::default()
+                },
+            )
+            .await;
+            if let SetOutcome::MatchWon(_) = outcome {
+                let match_info = push_event(
+                    conn,
+                    match_id,
+                    EventKind::Status,
+                    NewEvent {
+                        status: Some(MatchStatus::Finished),
+                        ..Default::default()
+                    },
+                )
+                .await;
+                advance_bracket(conn, &match_info).await;
+            }
+            true
+        }
+        _ => false,
+    }
+}
+
+pub async fn undo(conn: &mut PgConnection, match_id: i32) -> bool {
+    let Some(match_info) = lock_match(conn, match_id).await else {
+        return false;
+    };
+    let mut undoable = match_info.undoable_events();
+    let Some(last_event) = undoable.next() else {
+        return false;
+    };
+    if match_info.status == MatchStatus::Finished && !withdraw_bracket_advance(conn, &match_info).await
+    {
+        return false;
+    }
+    let mut reverted = vec![last_event];
+    if last_event.kind == EventKind::Status && last_event.status == Some(MatchStatus::Finished) {
+        reverted.extend(undoable.next().filter(|event| event.kind == EventKind::SetEnd));
+    }
+    if last_event.kind == EventKind::Point {
+        reverted.extend(match_info.penalty_of(last_event));
+    }
+    for event in reverted {
+        push_event(
+            conn,
+            match_id,
+            EventKind::Undo,
+            NewEvent {
+                team: event.team,
+                reverts: Some(event.id),
+                ..Default::default()
+            },
+        )
+        .await;
+    }
+    true
+}
+
+#[derive(Debug)]
+pub enum SubstitutionError {
+    MatchNotInProgress,
+    InvalidPlayerNumber,
+    SamePlayer,
+    LimitReached,
+}
+
+impl Display for SubstitutionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            SubstitutionError::MatchNotInProgress => {
+                "Substitutions can only be made in a match in progress"
+            }
+            SubstitutionError::InvalidPlayerNumber => "Player number has to be between 1 and 99",
+            SubstitutionError::SamePlayer => "Player in and player out have to be different",
+            SubstitutionError::LimitReached => "Team has used all substitutions in this set",
+        })
+    }
+}
+
+impl Error for SubstitutionError {}
+
+pub async fn substitute(
+    conn: &mut PgConnection,
+    match_id: i32,
+    team: Team,
+    player_in: &str,
+    player_out: &str,
+) -> Result<(), SubstitutionError> {
+    let Some(match_info) = lock_match(conn, match_id).await else {
+        return Err(SubstitutionError::MatchNotInProgress);
+    };
+    if match_info.status != MatchStatus::InProgress {
+        return Err(SubstitutionError::MatchNotInProgress);
+    }
+    let (Ok(player_in), Ok(player_out)) = (player_in.trim().parse(), player_out.trim().parse())
+    else {
+        return Err(SubstitutionError::InvalidPlayerNumber);
+    };
+    if !(1..=99).contains(&player_in) || !(1..=99).contains(&player_out) {
+        return Err(SubstitutionError::InvalidPlayerNumber);
+    }
+    if player_in == player_out {
+        return Err(SubstitutionError::SamePlayer);
+    }
+    if match_info.set_substitutions(team) as i32 >= match_info.rules.substitutions_per_set {
+        return Err(SubstitutionError::LimitReached);
+    }
+    push_event(
+        conn,
+        match_id,
+        EventKind::Substitution,
+        NewEvent {
+            team: Some(team),
+            player_in: Some(player_in),
+            player_out: Some(player_out),
+            ..Default::default()
+        },
+    )
+    .await;
+    Ok(())
+}
+
+#[derive(Debug)]
+pub enum SanctionError {
+    MatchNotInProgress,
+    InvalidPlayerNumber,
+}
+
+impl Display for SanctionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            SanctionError::MatchNotInProgress => {
+                "Sanctions can only be recorded in a match in progress"
+            }
+            SanctionError::InvalidPlayerNumber => {
+                "Player number has to be between 1 and 99. Leave empty to sanction the team"
+            }
+        })
+    }
+}
+
+impl Error for SanctionError {}
+
+pub async fn add_sanction(
+    conn: &mut PgConnection,
+    match_id: i32,
+    team: Team,
+    player: &str,
+    sanction: Sanction,
+) -> Result<(), SanctionError> {
+    let Some(match_info) = lock_match(conn, match_id).await else {
+        return Err(SanctionError::MatchNotInProgress);
+    };
+    if match_info.status != MatchStatus::InProgress {
+        return Err(SanctionError::MatchNotInProgress);
+    }
+    let player = if player.trim().is_empty() {
+        None
+    } else {
+        match player.trim().parse() {
+            Ok(player) if (1..=99).contains(&player) => Some(player),
+            _ => return Err(SanctionError::InvalidPlayerNumber),
+        }
+    };
+    push_event(
+        conn,
+        match_id,
+        EventKind::Sanction,
+        NewEvent {
+            team: Some(team),
+            sanction: Some(sanction),
+            player,
+            ..Default::default()
+        },
+    )
+    .await;
+    if sanction == Sanction::Penalty {
+        add_set_point(conn, match_id, team.opponent(), None, None).await;
+    }
+    Ok(())
+}
+
+pub struct Tie {
+    pub first_leg: Match,
+    pub second_leg: Match,
+    pub golden_set: Option<Match>,
+}
+
+impl Tie {
+    fn winner_of(match_info: &Match) -> Option<&str> {
+        if match_info.status != MatchStatus::Finished {
+            None
+        } else if match_info.result[0] > match_info.result[1] {
+            Some(&match_info.team_a)
+        } else {
+            Some(&match_info.team_b)
+        }
+    }
+
+    pub fn golden_set_needed(&self) -> bool {
+        matches!(
+            (Tie::winner_of(&self.first_leg), Tie::winner_of(&self.second_leg)),
+            (Some(first), Some(second)) if first != second
+        )
+    }
+
+    pub fn winner(&self) -> Option<&str> {
+        if self.golden_set_needed() {
+            return self.golden_set.as_ref().and_then(Tie::winner_of);
+        }
+        Tie::winner_of(&self.first_leg).filter(|_| Tie::winner_of(&self.second_leg).is_some())
+    }
+
+    pub fn match_ids(&self) -> Vec<i32> {
+        let mut ids = vec![self.first_leg.id, self.second_leg.id];
+        ids.extend(self.golden_set.as_ref().map(|golden_set| golden_set.id));
+        ids
+    }
+}
+
+pub async fn get_tie(conn: &mut PgConnection, match_id: i32) -> Option<Tie> {
+    let row = query!(
+        "SELECT first_leg, second_leg, golden_set FROM ties WHERE $1 IN (first_leg, second_leg, golden_set)",
+        match_id
+    )
+    .fetch_optional(conn.as_mut())
+    .await
+    .unwrap()?;
+    let golden_set = match row.golden_set {
+        Some(golden_set) => Some(get_match(conn, golden_set).await),
+        None => None,
+    };
+    Some(Tie {
+        first_leg: get_match(conn, row.first_leg).await,
+        second_leg: get_match(conn, row.second_leg).await,
+        golden_set,
+    })
+}
+
+pub async fn tie_candidates(conn: &mut PgConnection, match_info: &Match) -> Vec<Match> {
+    let ids = query!(
+        "SELECT id FROM matches m WHERE id<>$1 AND ((team_a_id=$2 AND team_b_id=$3) OR (team_a_id=$3 AND team_b_id=$2)) AND NOT EXISTS (SELECT 1 FROM ties WHERE m.id IN (first_leg, second_leg, golden_set)) ORDER BY match_start",
+        match_info.id,
+        match_info.team_a_id,
+        match_info.team_b_id
+    )
+    .fetch_all(conn.as_mut())
+    .await
+    .unwrap();
+    let mut candidates = Vec::new();
+    for row in ids {
+        candidates.push(get_match(conn, row.id).await);
+    }
+    candidates
+}
+
+#[derive(Debug)]
+pub enum TieError {
+    UnknownMatch,
+    SameMatch,
+    DifferentTeams,
+    AlreadyLinked,
+}
+
+impl Display for TieError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            TieError::UnknownMatch => "There is no match with this id",
+            TieError::SameMatch => "A match cannot be linked with itself",
+            TieError::DifferentTeams => "Both legs have to be played by the same teams",
+            TieError::AlreadyLinked => "One of the matches is already part of a tie",
+        })
+    }
+}
+
+impl Error for TieError {}
+
+pub async fn link_tie(
+    conn: &mut PgConnection,
+    match_id: i32,
+    other_leg: &str,
+) -> Result<(), TieError> {
+    let Ok(other_leg) = other_leg.trim().parse::<i32>() else {
+        return Err(TieError::UnknownMatch);
+    };
+    if other_leg == match_id {
+        return Err(TieError::SameMatch);
+    }
+    let Some(leg) = lock_match(conn, match_id).await else {
+        return Err(TieError::UnknownMatch);
+    };
+    let Some(other) = lock_match(conn, other_leg).await else {
+        return Err(TieError::UnknownMatch);
+    };
+    let same_teams = (leg.team_a_id == other.team_a_id && leg.team_b_id == other.team_b_id)
+        || (leg.team_a_id == other.team_b_id && leg.team_b_id == other.team_a_id);
+    if !same_teams {
+        return Err(TieError::DifferentTeams);
+    }
+    let linked = query!(
+        "SELECT id FROM ties WHERE $1 IN (first_leg, second_leg, golden_set) OR $2 IN (first_leg, second_leg, golden_set)",
+        match_id,
+        other_leg
+    )
+    .fetch_optional(conn.as_mut())
+    .await
+    .unwrap();
+    if linked.is_some() {
+        return Err(TieError::AlreadyLinked);
+    }
+    let (first_leg, second_leg) = if leg.match_start <= other.match_start {
+        (leg.id, other.id)
+    } else {
+        (other.id, leg.id)
+    };
+    query!(
+        "INSERT INTO ties(first_leg, second_leg) VALUES($1, $2)",
+        first_leg,
+        second_leg
+    )
+    .execute(conn)
+    .await
+    .unwrap();
+    Ok(())
+}
+
+pub async fn create_golden_set(conn: &mut PgConnection, match_id: i32) -> Option<Match> {
+    let tie_id = query!(
+        "SELECT id FROM ties WHERE $1 IN (first_leg, second_leg) FOR UPDATE",
+        match_id
+    )
+    .fetch_optional(conn.as_mut())
+    .await
+    .unwrap()?
+    .id;
+    let tie = get_tie(conn, match_id).await?;
+    if !tie.golden_set_needed() || tie.golden_set.is_some() || tie.second_leg.archived {
+        return None;
+    }
+    let second_leg = tie.second_leg;
+    let golden_set_id = query!(
+        "INSERT INTO matches(team_a_id, team_b_id, rules, status) VALUES($1, $2, $3, 'PLANNED') RETURNING id",
+        second_leg.team_a_id,
+        second_leg.team_b_id,
+        second_leg.rules.golden_set() as ScoringRules
+    )
+    .fetch_one(conn.as_mut())
+    .await
+    .unwrap()
+    .id;
+    query!(
+        "UPDATE ties SET golden_set=$2 WHERE id=$1",
+        tie_id,
+        golden_set_id
+    )
+    .execute(conn.as_mut())
+    .await
+    .unwrap();
+    Some(get_match(conn, golden_set_id).await)
+}
+
+#[derive(Debug)]
+pub enum PlayerAddError {
+    InvalidPlayerNumber,
+    NumberTaken,
+    PlayerNameEmpty,
+    PlayerNameTooLong,
+}
+
+impl Display for PlayerAddError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            PlayerAddError::InvalidPlayerNumber => "Player number has to be between 1 and 99",
+            PlayerAddError::NumberTaken => "This number is already taken in the roster",
+            PlayerAddError::PlayerNameEmpty => "Player name cannot be empty",
+            PlayerAddError::PlayerNameTooLong => "Player name can't be longer than 50 characters",
+        })
+    }
+}
+
+impl Error for PlayerAddError {}
+
+pub async fn add_player(
+    conn: &mut PgConnection,
+    team_id: i32,
+    number: &str,
+    name: &str,
+    role: PlayerRole,
+) -> Result<(), PlayerAddError> {
+    let number = match number.trim().parse::<i32>() {
+        Ok(number) if (1..=99).contains(&number) => number,
+        _ => return Err(PlayerAddError::InvalidPlayerNumber),
+    };
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(PlayerAddError::PlayerNameEmpty);
+    }
+    if name.chars().count() > 50 {
+        return Err(PlayerAddError::PlayerNameTooLong);
+    }
+    let inserted = query!(
+        "INSERT INTO players(team_id, number, name, role) VALUES($1, $2, $3, $4) ON CONFLICT DO NOTHING",
+        team_id,
+        number,
+        name,
+        role as PlayerRole
+    )
+    .execute(conn)
+    .await
+    .unwrap()
+    .rows_affected();
+    if inserted == 0 {
+        return Err(PlayerAddError::NumberTaken);
+    }
+    Ok(())
+}
+
+/// Removes a player from the roster of one of the teams playing the match
+pub async fn remove_player(conn: &mut PgConnection, match_id: i32, player_id: i32) -> bool {
+    query!(
+        "DELETE FROM players p USING matches m WHERE p.id = $2 AND m.id = $1 AND p.team_id IN (m.team_a_id, m.team_b_id)",
+        match_id,
+        player_id
+    )
+        .execute(conn)
+        .await
+        .unwrap()
+        .rows_affected()
+        > 0
+}
+
+pub async fn get_player(conn: &mut PgConnection, player_id: i32) -> Option<Player> {
+    query_as!(
+        Player,
+        r#"SELECT p.id, team_id, t.name team, number, p.name, role "role: PlayerRole" FROM players p JOIN teams t ON t.id=team_id WHERE p.id=$1"#,
+        player_id
+    )
+    .fetch_optional(conn)
+    .await
+    .unwrap()
+}
+
+/// Finished meetings of two teams regardless of home and away, the latest first
+pub async fn get_head_to_head(conn: &mut PgConnection, team_ids: [i32; 2]) -> Vec<Match> {
+    let ids = query!(
+        "SELECT id FROM matches WHERE status='FINISHED' AND ((team_a_id=$1 AND team_b_id=$2) OR (team_a_id=$2 AND team_b_id=$1)) ORDER BY match_start DESC",
+        team_ids[0],
+        team_ids[1]
+    )
+    .fetch_all(conn.as_mut())
+    .await
+    .unwrap();
+    let mut matches = Vec::new();
+    for row in ids {
+        matches.push(get_match(conn, row.id).await);
+    }
+    matches
+}
+
+pub async fn get_officials(conn: &mut PgConnection) -> Vec<Official> {
+    query_as!(Official, "SELECT id, name FROM officials ORDER BY name")
+        .fetch_all(conn)
+        .await
+        .unwrap()
+}
+
+pub async fn get_official(conn: &mut PgConnection, official_id: i32) -> Option<Official> {
+    query_as!(
+        Official,
+        "SELECT id, name FROM officials WHERE id=$1",
+        official_id
+    )
+    .fetch_optional(conn)
+    .await
+    .unwrap()
+}
+
+/// Matches the official is assigned to which are not finished or cancelled yet, with the assigned role
+pub async fn get_official_assignments(
+    conn: &mut PgConnection,
+    official_id: i32,
+) -> Vec<(Match, OfficialRole)> {
+    let rows = query!(
+        r#"SELECT m.id, role "role: OfficialRole" FROM match_officials JOIN matches m ON m.id=match_id WHERE official_id=$1 AND m.status NOT IN ('FINISHED', 'CANCELLED') ORDER BY match_start"#,
+        official_id
+    )
+    .fetch_all(conn.as_mut())
+    .await
+    .unwrap();
+    let mut assignments = Vec::new();
+    for row in rows {
+        assignments.push((get_match(conn, row.id).await, row.role));
+    }
+    assignments
+}
+
+#[derive(Debug)]
+pub enum OfficialAddError {
+    NameEmpty,
+    NameTooLong,
+    DuplicateName,
+}
+
+impl Display for OfficialAddError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            OfficialAddError::NameEmpty => "Official name cannot be empty",
+            OfficialAddError::NameTooLong => "Official name can't be longer than 50 characters",
+            OfficialAddError::DuplicateName => "An official with this name already exists",
+        })
+    }
+}
+
+impl Error for OfficialAddError {}
+
+pub async fn add_official(
+    conn: &mut PgConnection,
+    name: &str,
+) -> Result<Official, OfficialAddError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(OfficialAddError::NameEmpty);
+    }
+    if name.chars().count() > 50 {
+        return Err(OfficialAddError::NameTooLong);
+    }
+    query_as!(
+        Official,
+        "INSERT INTO officials(name) VALUES($1) ON CONFLICT DO NOTHING RETURNING id, name",
+        name
+    )
+    .fetch_optional(conn)
+    .await
+    .unwrap()
+    .ok_or(OfficialAddError::DuplicateName)
+}
+
+pub const LINE_JUDGES_PER_MATCH: usize = 4;
+
+#[derive(Debug)]
+pub enum OfficialAssignError {
+    MatchOver,
+    UnknownOfficial,
+    AlreadyAssigned,
+    RoleTaken,
+    TooManyLineJudges,
+    Booking(MatchAddError),
+}
+
+impl Display for OfficialAssignError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OfficialAssignError::MatchOver => {
+                f.write_str("Officials can't be assigned to finished or cancelled matches")
+            }
+            OfficialAssignError::UnknownOfficial => f.write_str("Unknown official"),
+            OfficialAssignError::AlreadyAssigned => {
+                f.write_str("This official is already assigned to the match")
+            }
+            OfficialAssignError::RoleTaken => {
+                f.write_str("Another official already has this role in the match")
+            }
+            OfficialAssignError::TooManyLineJudges => write!(
+                f,
+                "A match can't have more than {LINE_JUDGES_PER_MATCH} line judges"
+            ),
+            OfficialAssignError::Booking(e) => e.fmt(f),
+        }
+    }
+}
+
+impl Error for OfficialAssignError {}
+
+pub async fn assign_official(
+    conn: &mut PgConnection,
+    match_id: i32,
+    official_id: i32,
+    role: OfficialRole,
+) -> Result<(), OfficialAssignError> {
+    let Some(match_info) = lock_match(conn, match_id).await else {
+        return Err(OfficialAssignError::MatchOver);
+    };
+    if matches!(
+        match_info.status,
+        MatchStatus::Finished | MatchStatus::Cancelled
+    ) {
+        return Err(OfficialAssignError::MatchOver);
+    }
+    if get_official(conn, official_id).await.is_none() {
+        return Err(OfficialAssignError::UnknownOfficial);
+    }
+    if match_info.official_ids().contains(&official_id) {
+        return Err(OfficialAssignError::AlreadyAssigned);
+    }
+    let same_role = match_info
+        .officials
+        .iter()
+        .filter(|official| official.role == role)
+        .count();
+    if role == OfficialRole::LineJudge && same_role >= LINE_JUDGES_PER_MATCH {
+        return Err(OfficialAssignError::TooManyLineJudges);
+    }
+    if role != OfficialRole::LineJudge && same_role > 0 {
+        return Err(OfficialAssignError::RoleTaken);
+    }
+    if match_info.status != MatchStatus::Postponed {
+        check_bookings(
+            conn,
+            Some(match_id),
+            [match_info.team_a_id, match_info.team_b_id],
+            match_info.court_id,
+            &[official_id],
+            match_info.match_start,
+            match_info.rules,
+        )
+        .await
+        .map_err(OfficialAssignError::Booking)?;
+    }
+    query!(
+        "INSERT INTO match_officials(match_id, official_id, role) VALUES($1, $2, $3)",
+        match_id,
+        official_id,
+        role as OfficialRole
+    )
+    .execute(conn)
+    .await
+    .unwrap();
+    Ok(())
+}
+
+pub async fn remove_official(conn: &mut PgConnection, match_id: i32, official_id: i32) -> bool {
+    query!(
+        "DELETE FROM match_officials WHERE match_id=$1 AND official_id=$2 AND match_id IN (SELECT id FROM matches WHERE season_id=current_season())",
+        match_id,
+        official_id
+    )
+    .execute(conn)
+    .await
+    .unwrap()
+    .rows_affected()
+        > 0
+}
+
+pub async fn get_team_matches(conn: &mut PgConnection, team_id: i32) -> Vec<Match> {
+    let ids = query!(
+        "SELECT id FROM matches WHERE team_a_id=$1 OR team_b_id=$1 ORDER BY match_start",
+        team_id
+    )
+    .fetch_all(conn.as_mut())
+    .await
+    .unwrap();
+    let mut matches = Vec::new();
+    for row in ids {
+        matches.push(get_match(conn, row.id).await);
+    }
+    matches
+}
+
+pub async fn get_competitions(conn: &mut PgConnection) -> Vec<Competition> {
+    query_as!(
+        Competition,
+        "SELECT id, name FROM competitions WHERE season_id=current_season() ORDER BY name"
+    )
+    .fetch_all(conn)
+    .await
+    .unwrap()
+}
+
+pub async fn get_season_competitions(conn: &mut PgConnection, season_id: i32) -> Vec<Competition> {
+    query_as!(
+        Competition,
+        "SELECT id, name FROM competitions WHERE season_id=$1 ORDER BY name",
+        season_id
+    )
+    .fetch_all(conn)
+    .await
+    .unwrap()
+}
+
+pub async fn get_competition(conn: &mut PgConnection, competition_id: i32) -> Option<Competition> {
+    query_as!(
+        Competition,
+        "SELECT id, name FROM competitions WHERE id=$1",
+        competition_id
+    )
+    .fetch_optional(conn)
+    .await
+    .unwrap()
+}
+
+pub async fn get_competition_matches(conn: &mut PgConnection, competition_id: i32) -> Vec<Match> {
+    let ids = query!(
+        "SELECT m.id FROM matches m JOIN competitions c ON c.id=m.competition_id WHERE c.id=$1 AND m.season_id=c.season_id ORDER BY match_start",
+        competition_id
+    )
+    .fetch_all(conn.as_mut())
+    .await
+    .unwrap();
+    let mut matches = Vec::new();
+    for row in ids {
+        matches.push(get_match(conn, row.id).await);
+    }
+    matches
+}
+
+#[derive(Debug)]
+pub enum CompetitionAddError {
+    NameEmpty,
+    NameTooLong,
+    DuplicateName,
+}
+
+impl Display for CompetitionAddError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            CompetitionAddError::NameEmpty => "Competition name cannot be empty",
+            CompetitionAddError::NameTooLong => {
+                "Competition name can't be longer than 50 characters"
+            }
+            CompetitionAddError::DuplicateName => "A competition with this name already exists",
+        })
+    }
+}
+
+impl Error for CompetitionAddError {}
+
+pub async fn add_competition(
+    conn: &mut PgConnection,
+    name: &str,
+) -> Result<Competition, CompetitionAddError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(CompetitionAddError::NameEmpty);
+    }
+    if name.chars().count() > 50 {
+        return Err(CompetitionAddError::NameTooLong);
+    }
+    query_as!(
+        Competition,
+        "INSERT INTO competitions(name) VALUES($1) ON CONFLICT DO NOTHING RETURNING id, name",
+        name
+    )
+    .fetch_optional(conn)
+    .await
+    .unwrap()
+    .ok_or(CompetitionAddError::DuplicateName)
+}
+
+pub struct Venue {
+    pub id: i32,
+    pub name: String,
+    pub slot_minutes: i32,
+    pub courts: Vec<String>,
+}
+
+pub struct Court {
+    pub id: i32,
+    pub name: String,
+}
+
+pub async fn get_venues(conn: &mut PgConnection) -> Vec<Venue> {
+    query_as!(
+        Venue,
+        r#"SELECT v.id, v.name, slot_minutes, ARRAY(SELECT c.name FROM courts c WHERE c.venue_id=v.id ORDER BY c.id) "courts!" FROM venues v ORDER BY v.name"#
+    )
+    .fetch_all(conn)
+    .await
+    .unwrap()
+}
+
+pub async fn get_courts(conn: &mut PgConnection) -> Vec<Court> {
+    query_as!(
+        Court,
+        r#"SELECT c.id, v.name || ' ' || c.name "name!" FROM courts c JOIN venues v ON v.id=venue_id ORDER BY v.name, c.id"#
+    )
+    .fetch_all(conn)
+    .await
+    .unwrap()
+}
+
+#[derive(Debug)]
+pub enum VenueAddError {
+    NameEmpty,
+    NameTooLong,
+    DuplicateName,
+    InvalidCourtCount,
+    InvalidSlotLength,
+}
+
+impl Display for VenueAddError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            VenueAddError::NameEmpty => "Venue name cannot be empty",
+            VenueAddError::NameTooLong => "Venue name can't be longer than 50 characters",
+            VenueAddError::DuplicateName => "A venue with this name already exists",
+            VenueAddError::InvalidCourtCount => "A venue needs between 1 and 20 courts",
+            VenueAddError::InvalidSlotLength => "Time slots must be between 1 and 600 minutes",
+        })
+    }
+}
+
+impl Error for VenueAddError {}
+
+pub async fn add_venue(
+    conn: &mut PgConnection,
+    name: &str,
+    court_count: &str,
+    slot_minutes: &str,
+) -> Result<Venue, VenueAddError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(VenueAddError::NameEmpty);
+    }
+    if name.chars().count() > 50 {
+        return Err(VenueAddError::NameTooLong);
+    }
+    let court_count = match court_count.trim().parse::<i32>() {
+        Ok(count) if (1..=20).contains(&count) => count,
+        _ => return Err(VenueAddError::InvalidCourtCount),
+    };
+    let slot_minutes = match slot_minutes.trim().parse::<i32>() {
+        Ok(minutes) if (1..=600).contains(&minutes) => minutes,
+        _ => return Err(VenueAddError::InvalidSlotLength),
+    };
+    let venue_id = query!(
+        "INSERT INTO venues(name, slot_minutes) VALUES($1, $2) ON CONFLICT DO NOTHING RETURNING id",
+        name,
+        slot_minutes
+    )
+    .fetch_optional(conn.as_mut())
+    .await
+    .unwrap()
+    .ok_or(VenueAddError::DuplicateName)?
+    .id;
+    let courts: Vec<String> = (1..=court_count)
+        .map(|number| format!("Court {number}"))
+        .collect();
+    query!(
+        "INSERT INTO courts(venue_id, name) SELECT $1, UNNEST($2::VARCHAR[])",
+        venue_id,
+        &courts[..]
+    )
+    .execute(conn)
+    .await
+    .unwrap();
+    Ok(Venue {
+        id: venue_id,
+        name: name.to_owned(),
+        slot_minutes,
+        courts,
+    })
+}
+
+pub struct Season {
+    pub id: i32,
+    pub name: String,
+    pub started_at: PrimitiveDateTime,
+    pub current: bool,
+}
+
+pub async fn get_seasons(conn: &mut PgConnection) -> Vec<Season> {
+    query_as!(
+        Season,
+        r#"SELECT id, name, started_at, id = current_season() "current!" FROM seasons ORDER BY id DESC"#
+    )
+    .fetch_all(conn)
+    .await
+    .unwrap()
+}
+
+pub async fn get_season(conn: &mut PgConnection, season_id: i32) -> Option<Season> {
+    query_as!(
+        Season,
+        r#"SELECT id, name, started_at, id = current_season() "current!" FROM seasons WHERE id=$1"#,
+        season_id
+    )
+    .fetch_optional(conn)
+    .await
+    .unwrap()
+}
+
+pub async fn current_season(conn: &mut PgConnection) -> Season {
+    query_as!(
+        Season,
+        r#"SELECT id, name, started_at, TRUE "current!" FROM seasons WHERE id = current_season()"#
+    )
+    .fetch_one(conn)
+    .await
+    .unwrap()
+}
+
+#[derive(Debug)]
+pub enum SeasonAddError {
+    NameEmpty,
+    NameTooLong,
+    DuplicateName,
+}
+
+impl Display for SeasonAddError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            SeasonAddError::NameEmpty => "Season name cannot be empty",
+            SeasonAddError::NameTooLong => "Season name can't be longer than 50 characters",
+            SeasonAddError::DuplicateName => "A season with this name already exists",
+        })
+    }
+}
+
+impl Error for SeasonAddError {}
+
+/// Starts a new current season and archives the previous one, matches which are not
+/// finished or cancelled yet carry over to the new season
+pub async fn start_season(conn: &mut PgConnection, name: &str) -> Result<Season, SeasonAddError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(SeasonAddError::NameEmpty);
+    }
+    if name.chars().count() > 50 {
+        return Err(SeasonAddError::NameTooLong);
+    }
+    let previous = current_season(conn).await;
+    let season = query_as!(
+        Season,
+        r#"INSERT INTO seasons(name) VALUES($1) ON CONFLICT DO NOTHING RETURNING id, name, started_at, TRUE "current!""#,
+        name
+    )
+    .fetch_optional(conn.as_mut())
+    .await
+    .unwrap()
+    .ok_or(SeasonAddError::DuplicateName)?;
+    // Carried over matches leave the archived competitions for their namesakes in the new season,
+    // so do pool plays still waiting for their playoffs
+    query!(
+        "INSERT INTO competitions(name, season_id) SELECT c.name, $1::INT FROM competitions c JOIN matches m ON m.competition_id=c.id WHERE m.season_id=$2 AND m.status NOT IN ('FINISHED', 'CANCELLED') UNION SELECT c.name, $1 FROM competitions c JOIN pool_plays p ON p.competition_id=c.id WHERE c.season_id=$2 AND p.bracket_id IS NULL",
+        season.id,
+        previous.id
+    )
+    .execute(conn.as_mut())
+    .await
+    .unwrap();
+    query!(
+        "UPDATE matches m SET season_id=$1, competition_id=(SELECT n.id FROM competitions n JOIN competitions c ON c.name=n.name WHERE c.id=m.competition_id AND n.season_id=$1) WHERE season_id=$2 AND status NOT IN ('FINISHED', 'CANCELLED')",
+        season.id,
+        previous.id
+    )
+    .execute(conn.as_mut())
+    .await
+    .unwrap();
+    // Brackets, tournaments and pool plays with a carried over match draw their next matches in the new season
+    query!(
+        "UPDATE brackets b SET competition_id=n.id FROM competitions c, competitions n WHERE c.id=b.competition_id AND c.season_id=$2 AND n.name=c.name AND n.season_id=$1 AND EXISTS(SELECT 1 FROM bracket_nodes bn JOIN matches m ON m.id=bn.match_id WHERE bn.bracket_id=b.id AND m.season_id=$1)",
+        season.id,
+        previous.id
+    )
+    .execute(conn.as_mut())
+    .await
+    .unwrap();
+    query!(
+        "UPDATE tournaments t SET competition_id=n.id FROM competitions c, competitions n WHERE c.id=t.competition_id AND c.season_id=$2 AND n.name=c.name AND n.season_id=$1 AND EXISTS(SELECT 1 FROM tournament_pairings tp JOIN matches m ON m.id=tp.match_id WHERE tp.tournament_id=t.id AND m.season_id=$1)",
+        season.id,
+        previous.id
+    )
+    .execute(conn.as_mut())
+    .await
+    .unwrap();
+    query!(
+        "UPDATE pool_plays p SET competition_id=n.id FROM competitions c, competitions n WHERE c.id=p.competition_id AND c.season_id=$2 AND n.name=c.name AND n.season_id=$1 AND (p.bracket_id IS NULL OR EXISTS(SELECT 1 FROM pools JOIN pool_matches pm ON pm.pool_id=pools.id JOIN matches m ON m.id=pm.match_id WHERE pools.pool_play_id=p.id AND m.season_id=$1))",
+        season.id,
+        previous.id
+    )
+    .execute(conn)
+    .await
+    .unwrap();
+    Ok(season)
+}
+
+pub struct BracketInfo {
+    pub id: i32,
+    pub name: String,
+}
+
+pub struct BracketNode {
+    pub teams: [Option<String>; 2],
+    pub seeds: [Option<usize>; 2],
+    pub bye: bool,
+    pub match_info: Option<Match>,
+}
+
+impl BracketNode {
+    pub fn winner(&self) -> Option<&str> {
+        if self.bye {
+            return self.teams[0].as_deref();
+        }
+        self.match_info.as_ref().and_then(Tie::winner_of)
+    }
+}
+
+pub struct Bracket {
+    pub id: i32,
+    pub name: String,
+    pub rounds: Vec<Vec<BracketNode>>,
+}
+
+impl Bracket {
+    pub fn champion(&self) -> Option<&str> {
+        self.rounds.last()?.first()?.winner()
+    }
+
+    /// Match of the next round the winner of `match_id` goes to, if it was already drawn
+    pub fn next_match(&self, match_id: i32) -> Option<&Match> {
+        self.rounds.iter().enumerate().find_map(|(round, nodes)| {
+            let position = nodes.iter().position(|node| {
+                node.match_info
+                    .as_ref()
+                    .is_some_and(|match_info| match_info.id == match_id)
+            })?;
+            self.rounds.get(round + 1)?[position / 2].match_info.as_ref()
+        })
+    }
+}
+
+/// Seeds in bracket order, top seeds get the byes and can only meet in the late rounds
+fn seed_order(size: usize) -> Vec<usize> {
+    let mut order = vec![1];
+    while order.len() < size {
+        let count = order.len() * 2;
+        order = order
+            .iter()
+            .flat_map(|&seed| [seed, count + 1 - seed])
+            .collect();
+    }
+    order
+}
+
+pub async fn get_brackets(conn: &mut PgConnection) -> Vec<BracketInfo> {
+    query_as!(BracketInfo, "SELECT id, name FROM brackets ORDER BY name")
+        .fetch_all(conn)
+        .await
+        .unwrap()
+}
+
+pub async fn get_bracket(conn: &mut PgConnection, bracket_id: i32) -> Option<Bracket> {
+    let bracket = query_as!(
+        BracketInfo,
+        "SELECT id, name FROM brackets WHERE id=$1",
+        bracket_id
+    )
+    .fetch_optional(conn.as_mut())
+    .await
+    .unwrap()?;
+    let nodes = query!(
+        r#"SELECT round, position, a.name "team_a?", b.name "team_b?", match_id FROM bracket_nodes n LEFT JOIN teams a ON a.id=team_a_id LEFT JOIN teams b ON b.id=team_b_id WHERE bracket_id=$1 ORDER BY round, position"#,
+        bracket_id
+    )
+    .fetch_all(conn.as_mut())
+    .await
+    .unwrap();
+    let first_round = nodes.iter().filter(|node| node.round == 0).count();
+    let seeds = seed_order(first_round * 2);
+    let mut rounds: Vec<Vec<BracketNode>> = Vec::new();
+    for node in nodes {
+        let round = node.round as usize;
+        let position = node.position as usize;
+        let match_info = match node.match_id {
+            Some(match_id) => Some(get_match(conn, match_id).await),
+            None => None,
+        };
+        let seeds = if round == 0 {
+            [
+                node.team_a.as_ref().map(|_| seeds[position * 2]),
+                node.team_b.as_ref().map(|_| seeds[position * 2 + 1]),
+            ]
+        } else {
+            [None, None]
+        };
+        if rounds.len() == round {
+            rounds.push(Vec::new());
+        }
+        rounds[round].push(BracketNode {
+            bye: round == 0 && node.team_b.is_none(),
+            teams: [node.team_a, node.team_b],
+            seeds,
+            match_info,
+        });
+    }
+    Some(Bracket {
+        id: bracket.id,
+        name: bracket.name,
+        rounds,
+    })
+}
+
+/// A bracket match decides who advances, so it can only end in a result or a forfeit
+pub async fn in_bracket(conn: &mut PgConnection, match_id: i32) -> bool {
+    query!(
+        "SELECT match_id FROM bracket_nodes WHERE match_id=$1",
+        match_id
+    )
+    .fetch_optional(conn)
+    .await
+    .unwrap()
+    .is_some()
+}
+
+pub async fn get_match_bracket(conn: &mut PgConnection, match_id: i32) -> Option<Bracket> {
+    let bracket_id = query!(
+        "SELECT bracket_id FROM bracket_nodes WHERE match_id=$1",
+        match_id
+    )
+    .fetch_optional(conn.as_mut())
+    .await
+    .unwrap()?
+    .bracket_id;
+    get_bracket(conn, bracket_id).await
+}
+
+#[derive(Debug)]
+pub enum BracketAddError {
+    NameEmpty,
+    NameTooLong,
+    DuplicateName,
+    NotEnoughTeams,
+    DuplicateTeam,
+    InvalidRoundInterval,
+    Match(MatchAddError),
+}
+
+impl Display for BracketAddError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BracketAddError::NameEmpty => f.write_str("Bracket name cannot be empty"),
+            BracketAddError::NameTooLong => {
+                f.write_str("Bracket name can't be longer than 50 characters")
+            }
+            BracketAddError::DuplicateName => {
+                f.write_str("A bracket with this name already exists")
+            }
+            BracketAddError::NotEnoughTeams => f.write_str("At least two teams are needed"),
+            BracketAddError::DuplicateTeam => f.write_str("Every team can be listed only once"),
+            BracketAddError::InvalidRoundInterval => {
+                f.write_str("Days between rounds have to be between 0 and 365")
+            }
+            BracketAddError::Match(e) => e.fmt(f),
+        }
+    }
+}
+
+impl Error for BracketAddError {}
+
+/// Creates a single-elimination bracket with `teams` listed from the top seed down,
+/// the first round matches are drawn right away
+pub async fn add_bracket(
+    conn: &mut PgConnection,
+    name: &str,
+    teams: &[&str],
+    first_round: &str,
+    round_interval: &str,
+    ruleset: &str,
+    competition: &str,
+) -> Result<Bracket, BracketAddError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(BracketAddError::NameEmpty);
+    }
+    if name.chars().count() > 50 {
+        return Err(BracketAddError::NameTooLong);
+    }
+    let teams: Vec<String> = teams
+        .iter()
+        .map(|team| team.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|team| !team.is_empty())
+        .collect();
+    if teams.len() < 2 {
+        return Err(BracketAddError::NotEnoughTeams);
+    }
+    if teams.iter().any(|team| team.chars().count() > 50) {
+        return Err(BracketAddError::Match(MatchAddError::TeamNameTooLong));
+    }
+    let keys: HashSet<String> = teams.iter().map(|team| team_key(team)).collect();
+    if keys.len() != teams.len() {
+        return Err(BracketAddError::DuplicateTeam);
+    }
+    let first_round = parse_future_date(first_round).map_err(BracketAddError::Match)?;
+    let round_interval = match round_interval.trim().parse::<i32>() {
+        Ok(days) if (0..=365).contains(&days) => days,
+        _ => return Err(BracketAddError::InvalidRoundInterval),
+    };
+    let Some(rules) = ScoringRules::preset(ruleset) else {
+        return Err(BracketAddError::Match(MatchAddError::UnknownRuleset));
+    };
+    let competition_id = parse_competition(conn, competition)
+        .await
+        .map_err(BracketAddError::Match)?;
+    let Some(bracket_id) = query!(
+        "INSERT INTO brackets(name, first_round, round_interval, rules, competition_id) VALUES($1, $2, $3, $4, $5) ON CONFLICT DO NOTHING RETURNING id",
+        name,
+        first_round,
+        round_interval,
+        rules as ScoringRules,
+        competition_id
+    )
+    .fetch_optional(conn.as_mut())
+    .await
+    .unwrap()
+    .map(|row| row.id) else {
+        return Err(BracketAddError::DuplicateName);
+    };
+    let mut team_ids = Vec::new();
+    for team in &teams {
+        team_ids.push(find_or_create_team(conn, team).await);
+    }
+    let size = teams.len().next_power_of_two();
+    let mut nodes = size / 2;
+    let mut round = 0;
+    while nodes > 0 {
+        query!(
+            "INSERT INTO bracket_nodes(bracket_id, round, position) SELECT $1, $2, generate_series(0, $3 - 1)",
+            bracket_id,
+            round,
+            nodes as i32
         )
         .execute(conn.as_mut())
         .await
         .unwrap();
-    } else {
+        nodes /= 2;
+        round += 1;
+    }
+    let seeds = seed_order(size);
+    for (position, pair) in seeds.chunks(2).enumerate() {
+        let team_a_id = team_ids[pair[0] - 1];
+        let team_b_id = team_ids.get(pair[1] - 1).copied();
         query!(
-                "UPDATE matches SET set_results_a[array_length(set_results_a,1)+1]=0,set_results_b[array_length(set_results_b,1)+1]=0,set_start=now() WHERE id=$1",
-                match_id
-            )
-            .execute( conn.as_mut())
-            .await
-            .unwrap();
-    }
-    QueryBuilder::new("UPDATE matches SET result[")
-        .push(result_index + 1)
-        .push("]=result[")
-        .push(result_index + 1)
-        .push("]+1 WHERE id=$1")
-        .build()
-        .bind(match_id)
+            "UPDATE bracket_nodes SET team_a_id=$3, team_b_id=$4 WHERE bracket_id=$1 AND round=0 AND position=$2",
+            bracket_id,
+            position as i32,
+            team_a_id,
+            team_b_id
+        )
         .execute(conn.as_mut())
         .await
         .unwrap();
+        match team_b_id {
+            Some(team_b_id) => {
+                create_bracket_match(conn, bracket_id, 0, position as i32, team_a_id, team_b_id)
+                    .await;
+            }
+            None => {
+                advance_team(conn, bracket_id, 0, position as i32, team_a_id).await;
+            }
+        }
+    }
+    Ok(get_bracket(conn, bracket_id).await.unwrap())
+}
+
+async fn create_bracket_match(
+    conn: &mut PgConnection,
+    bracket_id: i32,
+    round: i32,
+    position: i32,
+    team_a_id: i32,
+    team_b_id: i32,
+) {
+    let bracket = query!(
+        r#"SELECT first_round, round_interval, rules "rules: ScoringRules", competition_id FROM brackets WHERE id=$1"#,
+        bracket_id
+    )
+    .fetch_one(conn.as_mut())
+    .await
+    .unwrap();
+    let now = OffsetDateTime::now_utc();
+    let date = (bracket.first_round + Duration::days((round * bracket.round_interval).into()))
+        .max(PrimitiveDateTime::new(now.date(), now.time()));
+    let match_id = query!(
+        "INSERT INTO matches(match_start, set_start, team_a_id, team_b_id, rules, competition_id, status) VALUES($1, $1, $2, $3, $4, $5, 'PLANNED') RETURNING id",
+        date,
+        team_a_id,
+        team_b_id,
+        bracket.rules as ScoringRules,
+        bracket.competition_id
+    )
+    .fetch_one(conn.as_mut())
+    .await
+    .unwrap()
+    .id;
+    query!(
+        "UPDATE bracket_nodes SET match_id=$4 WHERE bracket_id=$1 AND round=$2 AND position=$3",
+        bracket_id,
+        round,
+        position,
+        match_id
+    )
+    .execute(conn)
+    .await
+    .unwrap();
+}
+
+/// Puts the winner of a node into the next round, the match there is drawn once both teams are known
+async fn advance_team(
+    conn: &mut PgConnection,
+    bracket_id: i32,
+    round: i32,
+    position: i32,
+    team_id: i32,
+) {
+    let Some(next) = query!(
+        "UPDATE bracket_nodes SET team_a_id=CASE WHEN $3::INT % 2 = 0 THEN $4 ELSE team_a_id END, team_b_id=CASE WHEN $3::INT % 2 = 1 THEN $4 ELSE team_b_id END WHERE bracket_id=$1 AND round=$2 + 1 AND position=$3::INT / 2 RETURNING round, position, team_a_id, team_b_id",
+        bracket_id,
+        round,
+        position,
+        team_id
+    )
+    .fetch_optional(conn.as_mut())
+    .await
+    .unwrap() else {
+        return;
+    };
+    if let (Some(team_a_id), Some(team_b_id)) = (next.team_a_id, next.team_b_id) {
+        create_bracket_match(conn, bracket_id, next.round, next.position, team_a_id, team_b_id)
+            .await;
+    }
+}
+
+async fn advance_bracket(conn: &mut PgConnection, match_info: &Match) {
+    if match_info.status != MatchStatus::Finished {
+        return;
+    }
+    let Some(node) = query!(
+        "SELECT bracket_id, round, position FROM bracket_nodes WHERE match_id=$1",
+        match_info.id
+    )
+    .fetch_optional(conn.as_mut())
+    .await
+    .unwrap() else {
+        return;
+    };
+    let winner = if match_info.result[0] > match_info.result[1] {
+        match_info.team_a_id
+    } else {
+        match_info.team_b_id
+    };
+    advance_team(conn, node.bracket_id, node.round, node.position, winner).await;
+}
+
+/// Takes the winner of a finished match back out of the next round,
+/// refused once the next match has been drawn
+async fn withdraw_bracket_advance(conn: &mut PgConnection, match_info: &Match) -> bool {
+    let Some(node) = query!(
+        "SELECT bracket_id, round, position FROM bracket_nodes WHERE match_id=$1",
+        match_info.id
+    )
+    .fetch_optional(conn.as_mut())
+    .await
+    .unwrap() else {
+        return true;
+    };
+    let Some(next) = query!(
+        "SELECT match_id FROM bracket_nodes WHERE bracket_id=$1 AND round=$2 + 1 AND position=$3::INT / 2 FOR UPDATE",
+        node.bracket_id,
+        node.round,
+        node.position
+    )
+    .fetch_optional(conn.as_mut())
+    .await
+    .unwrap() else {
+        return true;
+    };
+    if next.match_id.is_some() {
+        return false;
+    }
+    query!(
+        "UPDATE bracket_nodes SET team_a_id=CASE WHEN $3::INT % 2 = 0 THEN NULL ELSE team_a_id END, team_b_id=CASE WHEN $3::INT % 2 = 1 THEN NULL ELSE team_b_id END WHERE bracket_id=$1 AND round=$2 + 1 AND position=$3::INT / 2",
+        node.bracket_id,
+        node.round,
+        node.position
+    )
+    .execute(conn)
+    .await
+    .unwrap();
     true
 }
+
+pub struct TournamentInfo {
+    pub id: i32,
+    pub name: String,
+    pub format: TournamentFormat,
+}
+
+pub struct TournamentTeam {
+    pub id: i32,
+    pub name: String,
+    pub seed: i32,
+}
+
+pub struct Pairing {
+    pub round: i32,
+    pub team_a_id: i32,
+    pub team_b_id: Option<i32>,
+    pub match_info: Option<Match>,
+}
+
+pub struct Tournament {
+    pub id: i32,
+    pub name: String,
+    pub format: TournamentFormat,
+    pub teams: Vec<TournamentTeam>,
+    pub pairings: Vec<Pairing>,
+}
+
+impl Tournament {
+    pub fn rounds(&self) -> i32 {
+        self.pairings
+            .iter()
+            .map(|pairing| pairing.round + 1)
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub fn team_name(&self, team_id: i32) -> &str {
+        self.teams
+            .iter()
+            .find(|team| team.id == team_id)
+            .map_or("", |team| &team.name)
+    }
+}
+
+pub async fn get_tournaments(conn: &mut PgConnection) -> Vec<TournamentInfo> {
+    query_as!(
+        TournamentInfo,
+        r#"SELECT id, name, format "format: TournamentFormat" FROM tournaments ORDER BY name"#
+    )
+    .fetch_all(conn)
+    .await
+    .unwrap()
+}
+
+pub async fn get_tournament(conn: &mut PgConnection, tournament_id: i32) -> Option<Tournament> {
+    let tournament = query_as!(
+        TournamentInfo,
+        r#"SELECT id, name, format "format: TournamentFormat" FROM tournaments WHERE id=$1"#,
+        tournament_id
+    )
+    .fetch_optional(conn.as_mut())
+    .await
+    .unwrap()?;
+    let teams = query_as!(
+        TournamentTeam,
+        "SELECT t.id, t.name, seed FROM tournament_teams JOIN teams t ON t.id=team_id WHERE tournament_id=$1 ORDER BY seed",
+        tournament_id
+    )
+    .fetch_all(conn.as_mut())
+    .await
+    .unwrap();
+    let rows = query!(
+        "SELECT round, team_a_id, team_b_id, match_id FROM tournament_pairings WHERE tournament_id=$1 ORDER BY round, id",
+        tournament_id
+    )
+    .fetch_all(conn.as_mut())
+    .await
+    .unwrap();
+    let mut pairings = Vec::new();
+    for row in rows {
+        let match_info = match row.match_id {
+            Some(match_id) => Some(get_match(conn, match_id).await),
+            None => None,
+        };
+        pairings.push(Pairing {
+            round: row.round,
+            team_a_id: row.team_a_id,
+            team_b_id: row.team_b_id,
+            match_info,
+        });
+    }
+    Some(Tournament {
+        id: tournament.id,
+        name: tournament.name,
+        format: tournament.format,
+        teams,
+        pairings,
+    })
+}
+
+pub async fn get_match_tournament(conn: &mut PgConnection, match_id: i32) -> Option<Tournament> {
+    let tournament_id = query!(
+        "SELECT tournament_id FROM tournament_pairings WHERE match_id=$1",
+        match_id
+    )
+    .fetch_optional(conn.as_mut())
+    .await
+    .unwrap()?
+    .tournament_id;
+    get_tournament(conn, tournament_id).await
+}
+
+#[derive(Debug)]
+pub enum TournamentAddError {
+    NameEmpty,
+    NameTooLong,
+    DuplicateName,
+    NotEnoughTeams,
+    DuplicateTeam,
+    Match(MatchAddError),
+}
+
+impl Display for TournamentAddError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TournamentAddError::NameEmpty => f.write_str("Tournament name cannot be empty"),
+            TournamentAddError::NameTooLong => {
+                f.write_str("Tournament name can't be longer than 50 characters")
+            }
+            TournamentAddError::DuplicateName => {
+                f.write_str("A tournament with this name already exists")
+            }
+            TournamentAddError::NotEnoughTeams => f.write_str("At least two teams are needed"),
+            TournamentAddError::DuplicateTeam => {
+                f.write_str("Every team can be listed only once")
+            }
+            TournamentAddError::Match(e) => e.fmt(f),
+        }
+    }
+}
+
+impl Error for TournamentAddError {}
+
+/// Registers the teams of a tournament from the top seed down, rounds are generated one by one later
+pub async fn add_tournament(
+    conn: &mut PgConnection,
+    name: &str,
+    format: TournamentFormat,
+    teams: &[&str],
+    ruleset: &str,
+    competition: &str,
+) -> Result<TournamentInfo, TournamentAddError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(TournamentAddError::NameEmpty);
+    }
+    if name.chars().count() > 50 {
+        return Err(TournamentAddError::NameTooLong);
+    }
+    let teams: Vec<String> = teams
+        .iter()
+        .map(|team| team.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|team| !team.is_empty())
+        .collect();
+    if teams.len() < 2 {
+        return Err(TournamentAddError::NotEnoughTeams);
+    }
+    if teams.iter().any(|team| team.chars().count() > 50) {
+        return Err(TournamentAddError::Match(MatchAddError::TeamNameTooLong));
+    }
+    let keys: HashSet<String> = teams.iter().map(|team| team_key(team)).collect();
+    if keys.len() != teams.len() {
+        return Err(TournamentAddError::DuplicateTeam);
+    }
+    let Some(rules) = ScoringRules::preset(ruleset) else {
+        return Err(TournamentAddError::Match(MatchAddError::UnknownRuleset));
+    };
+    let competition_id = parse_competition(conn, competition)
+        .await
+        .map_err(TournamentAddError::Match)?;
+    let Some(tournament) = query_as!(
+        TournamentInfo,
+        r#"INSERT INTO tournaments(name, format, rules, competition_id) VALUES($1, $2, $3, $4) ON CONFLICT DO NOTHING RETURNING id, name, format "format: TournamentFormat""#,
+        name,
+        format as TournamentFormat,
+        rules as ScoringRules,
+        competition_id
+    )
+    .fetch_optional(conn.as_mut())
+    .await
+    .unwrap() else {
+        return Err(TournamentAddError::DuplicateName);
+    };
+    for (seed, team) in teams.iter().enumerate() {
+        let team_id = find_or_create_team(conn, team).await;
+        query!(
+            "INSERT INTO tournament_teams(tournament_id, team_id, seed) VALUES($1, $2, $3)",
+            tournament.id,
+            team_id,
+            seed as i32 + 1
+        )
+        .execute(conn.as_mut())
+        .await
+        .unwrap();
+    }
+    Ok(tournament)
+}
+
+/// Holds the tournament until the transaction ends, so that only one new round is paired at a time
+pub async fn lock_tournament(conn: &mut PgConnection, tournament_id: i32) -> bool {
+    query!(
+        "SELECT id FROM tournaments WHERE id=$1 FOR UPDATE",
+        tournament_id
+    )
+    .fetch_optional(conn)
+    .await
+    .unwrap()
+    .is_some()
+}
+
+/// Stores the pairings of a new round, a pairing without an opponent is a bye
+pub async fn add_tournament_round(
+    conn: &mut PgConnection,
+    tournament_id: i32,
+    pairings: &[(i32, Option<i32>)],
+    date: PrimitiveDateTime,
+) -> Vec<Match> {
+    // A tournament paused between rounds when the season changed goes on in the new season
+    query!(
+        "INSERT INTO competitions(name) SELECT c.name FROM tournaments t JOIN competitions c ON c.id=t.competition_id WHERE t.id=$1 AND c.season_id<>current_season() ON CONFLICT DO NOTHING",
+        tournament_id
+    )
+    .execute(conn.as_mut())
+    .await
+    .unwrap();
+    query!(
+        "UPDATE tournaments t SET competition_id=n.id FROM competitions c, competitions n WHERE t.id=$1 AND c.id=t.competition_id AND c.season_id<>current_season() AND n.name=c.name AND n.season_id=current_season()",
+        tournament_id
+    )
+    .execute(conn.as_mut())
+    .await
+    .unwrap();
+    let tournament = query!(
+        r#"SELECT rules "rules: ScoringRules", competition_id, (SELECT COALESCE(MAX(round) + 1, 0) FROM tournament_pairings WHERE tournament_id=$1) "round!" FROM tournaments WHERE id=$1 FOR UPDATE"#,
+        tournament_id
+    )
+    .fetch_one(conn.as_mut())
+    .await
+    .unwrap();
+    let mut matches = Vec::new();
+    for &(team_a_id, team_b_id) in pairings {
+        let match_id = match team_b_id {
+            Some(team_b_id) => Some(
+                query!(
+                    "INSERT INTO matches(match_start, set_start, team_a_id, team_b_id, rules, competition_id, status) VALUES($1, $1, $2, $3, $4, $5, 'PLANNED') RETURNING id",
+                    date,
+                    team_a_id,
+                    team_b_id,
+                    tournament.rules as ScoringRules,
+                    tournament.competition_id
+                )
+                .fetch_one(conn.as_mut())
+                .await
+                .unwrap()
+                .id,
+            ),
+            None => None,
+        };
+        query!(
+            "INSERT INTO tournament_pairings(tournament_id, round, team_a_id, team_b_id, match_id) VALUES($1, $2, $3, $4, $5)",
+            tournament_id,
+            tournament.round,
+            team_a_id,
+            team_b_id,
+            match_id
+        )
+        .execute(conn.as_mut())
+        .await
+        .unwrap();
+        if let Some(match_id) = match_id {
+            matches.push(get_match(conn, match_id).await);
+        }
+    }
+    matches
+}
+
+pub struct PoolPlayInfo {
+    pub id: i32,
+    pub name: String,
+}
+
+pub struct Pool {
+    pub name: String,
+    pub matches: Vec<Match>,
+}
+
+pub struct PoolPlay {
+    pub id: i32,
+    pub name: String,
+    pub tie_breakers: Vec<TieBreaker>,
+    pub advancing: i32,
+    pub competition_id: Option<i32>,
+    pub bracket_id: Option<i32>,
+    pub pools: Vec<Pool>,
+}
+
+impl PoolPlay {
+    /// A cancelled match is decided too, it just does not count in the standings
+    pub fn finished(&self) -> bool {
+        self.pools
+            .iter()
+            .flat_map(|pool| &pool.matches)
+            .all(|match_info| {
+                matches!(match_info.status, MatchStatus::Finished | MatchStatus::Cancelled)
+            })
+    }
+}
+
+pub async fn get_pool_plays(conn: &mut PgConnection) -> Vec<PoolPlayInfo> {
+    query_as!(PoolPlayInfo, "SELECT id, name FROM pool_plays ORDER BY name")
+        .fetch_all(conn)
+        .await
+        .unwrap()
+}
+
+pub async fn get_pool_play(conn: &mut PgConnection, pool_play_id: i32) -> Option<PoolPlay> {
+    let row = query!(
+        r#"SELECT id, name, tie_breakers "tie_breakers: Vec<TieBreaker>", advancing, competition_id, bracket_id FROM pool_plays WHERE id=$1"#,
+        pool_play_id
+    )
+    .fetch_optional(conn.as_mut())
+    .await
+    .unwrap()?;
+    let pool_rows = query!(
+        "SELECT id, name FROM pools WHERE pool_play_id=$1 ORDER BY id",
+        pool_play_id
+    )
+    .fetch_all(conn.as_mut())
+    .await
+    .unwrap();
+    let mut pools = Vec::new();
+    for pool in pool_rows {
+        let ids = query!(
+            "SELECT match_id FROM pool_matches JOIN matches m ON m.id=match_id WHERE pool_id=$1 ORDER BY match_start, match_id",
+            pool.id
+        )
+        .fetch_all(conn.as_mut())
+        .await
+        .unwrap();
+        let mut matches = Vec::new();
+        for id in ids {
+            matches.push(get_match(conn, id.match_id).await);
+        }
+        pools.push(Pool {
+            name: pool.name,
+            matches,
+        });
+    }
+    Some(PoolPlay {
+        id: row.id,
+        name: row.name,
+        tie_breakers: row.tie_breakers,
+        advancing: row.advancing,
+        competition_id: row.competition_id,
+        bracket_id: row.bracket_id,
+        pools,
+    })
+}
+
+pub async fn get_match_pool_play(conn: &mut PgConnection, match_id: i32) -> Option<PoolPlay> {
+    let pool_play_id = query!(
+        "SELECT pool_play_id FROM pool_matches JOIN pools p ON p.id=pool_id WHERE match_id=$1",
+        match_id
+    )
+    .fetch_optional(conn.as_mut())
+    .await
+    .unwrap()?
+    .pool_play_id;
+    get_pool_play(conn, pool_play_id).await
+}
+
+/// Returns `None` when the name is already taken
+pub async fn add_pool_play(
+    conn: &mut PgConnection,
+    name: &str,
+    tie_breakers: &[TieBreaker],
+    advancing: i32,
+    competition_id: Option<i32>,
+) -> Option<i32> {
+    query!(
+        "INSERT INTO pool_plays(name, tie_breakers, advancing, competition_id) VALUES($1, $2, $3, $4) ON CONFLICT DO NOTHING RETURNING id",
+        name,
+        tie_breakers as &[TieBreaker],
+        advancing,
+        competition_id
+    )
+    .fetch_optional(conn)
+    .await
+    .unwrap()
+    .map(|row| row.id)
+}
+
+pub async fn add_pool(conn: &mut PgConnection, pool_play_id: i32, name: &str) -> i32 {
+    query!(
+        "INSERT INTO pools(pool_play_id, name) VALUES($1, $2) RETURNING id",
+        pool_play_id,
+        name
+    )
+    .fetch_one(conn)
+    .await
+    .unwrap()
+    .id
+}
+
+pub async fn add_pool_match(conn: &mut PgConnection, pool_id: i32, match_id: i32) {
+    query!(
+        "INSERT INTO pool_matches(pool_id, match_id) VALUES($1, $2)",
+        pool_id,
+        match_id
+    )
+    .execute(conn)
+    .await
+    .unwrap();
+}
+
+/// Returns `false` when the playoffs were already created
+pub async fn set_pool_play_bracket(
+    conn: &mut PgConnection,
+    pool_play_id: i32,
+    bracket_id: i32,
+) -> bool {
+    query!(
+        "UPDATE pool_plays SET bracket_id=$2 WHERE id=$1 AND bracket_id IS NULL",
+        pool_play_id,
+        bracket_id
+    )
+    .execute(conn)
+    .await
+    .unwrap()
+    .rows_affected()
+        > 0
+}
+
+#[cfg(test)]
+impl Match {
+    /// Finished match where every set the winner takes ends 25:20
+    pub fn finished(id: i32, teams: [(i32, &str); 2], result: [i32; 2]) -> Match {
+        let mut match_info = Match::project(
+            MatchRow {
+                id,
+                match_start: PrimitiveDateTime::MIN,
+                team_a: teams[0].1.to_owned(),
+                team_b: teams[1].1.to_owned(),
+                team_a_id: teams[0].0,
+                team_b_id: teams[1].0,
+                competition_id: None,
+                court_id: None,
+                court: None,
+                season_id: 1,
+                archived: false,
+                rules: crate::rules::PRESETS[0].1,
+            },
+            Vec::new(),
+        );
+        let sets = [[25, 20]].repeat(result[0] as usize);
+        let sets = sets.into_iter().chain([[20, 25]].repeat(result[1] as usize));
+        (match_info.set_results_a, match_info.set_results_b) = sets.map(|[a, b]| (a, b)).unzip();
+        match_info.result = result.to_vec();
+        match_info.status = MatchStatus::Finished;
+        match_info
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seed_order_keeps_top_seeds_apart() {
+        assert_eq!(seed_order(8), vec![1, 8, 4, 5, 2, 7, 3, 6]);
+        for size in [2, 4, 8, 16, 32] {
+            let order = seed_order(size);
+            for pair in order.chunks(2) {
+                assert_eq!(pair[0] + pair[1], size + 1);
+            }
+            if size > 2 {
+                let top_half = &order[..size / 2];
+                assert!(top_half.contains(&1) && !top_half.contains(&2));
+            }
+        }
+    }
+
+    #[test]
+    fn seed_order_gives_byes_to_top_seeds() {
+        for teams in [3usize, 5, 6, 7, 12, 13] {
+            let size = teams.next_power_of_two();
+            let mut byes: Vec<usize> = seed_order(size)
+                .chunks(2)
+                .filter(|pair| pair[1] > teams)
+                .map(|pair| pair[0])
+                .collect();
+            byes.sort();
+            assert_eq!(byes, (1..=size - teams).collect::<Vec<_>>());
+            assert!(seed_order(size).chunks(2).all(|pair| pair[0] <= teams));
+        }
+    }
+}
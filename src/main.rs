@@ -1,7 +1,8 @@
 use axum::{
     extract::{
+        rejection::FormRejection,
         ws::{Message, WebSocket},
-        Path, State, WebSocketUpgrade,
+        Path, Query, State, WebSocketUpgrade,
     },
     http::StatusCode,
     response::IntoResponse,
@@ -18,14 +19,1227 @@ use tokio::{
     net::TcpListener,
     sync::{Mutex, RwLock},
 };
+use time::Weekday;
 use uuid::Uuid;
 
+mod api;
+mod countdown;
 mod db;
 mod markup;
+mod rules;
+mod schedule;
+mod standings;
 mod ws;
 
-async fn index_handler(HxBoosted(boosted): HxBoosted) -> impl IntoResponse {
-    let body = markup::main_page();
+async fn index_handler(
+    State(state): State<AppState>,
+    HxBoosted(boosted): HxBoosted,
+) -> impl IntoResponse {
+    let mut conn = state.pool.acquire().await.unwrap();
+    let season = db::current_season(&mut conn).await;
+    let team_names = db::get_team_names(&mut conn).await;
+    let competitions = db::get_competitions(&mut conn).await;
+    let courts = db::get_courts(&mut conn).await;
+    let body = markup::main_page(&season, &team_names, &competitions, &courts);
+    if boosted {
+        body
+    } else {
+        markup::index(body)
+    }
+}
+
+#[derive(Deserialize)]
+struct AddMatchForm {
+    date: String,
+    team_a: String,
+    team_b: String,
+    rules: String,
+    competition: String,
+    court: String,
+}
+
+async fn add_match_handler(
+    State(state): State<AppState>,
+    Form(form): Form<AddMatchForm>,
+) -> impl IntoResponse {
+    let mut tx = state.pool.begin().await.unwrap();
+    match db::add_match(
+        &mut tx,
+        &form.team_a,
+        &form.team_b,
+        &form.date,
+        &form.rules,
+        &form.competition,
+        &form.court,
+    )
+    .await
+    {
+        Ok(match_info) => {
+            tx.commit().await.unwrap();
+            state
+                .clients
+                .send_to_clients(
+                    ClientView::MainPage,
+                    &Message::Text(markup::add_match_entry(&match_info).into_string()),
+                )
+                .await;
+            send_team_update(&state, [match_info.team_a_id, match_info.team_b_id]).await;
+            ().into_response()
+        }
+        Err(e) => markup::error(&e.to_string()).into_response(),
+    }
+}
+
+async fn competitions_handler(
+    State(state): State<AppState>,
+    HxBoosted(boosted): HxBoosted,
+) -> impl IntoResponse {
+    let competitions = db::get_competitions(&mut state.pool.acquire().await.unwrap()).await;
+    let body = markup::competitions_page(&competitions);
+    if boosted {
+        body
+    } else {
+        markup::index(body)
+    }
+}
+
+#[derive(Deserialize)]
+struct AddCompetitionForm {
+    name: String,
+}
+
+async fn add_competition_handler(
+    State(state): State<AppState>,
+    Form(form): Form<AddCompetitionForm>,
+) -> impl IntoResponse {
+    let mut conn = state.pool.acquire().await.unwrap();
+    match db::add_competition(&mut conn, &form.name).await {
+        Ok(competition) => markup::add_competition_entry(&competition).into_response(),
+        Err(e) => markup::error(&e.to_string()).into_response(),
+    }
+}
+
+async fn competition_handler(
+    State(state): State<AppState>,
+    path: Option<Path<i32>>,
+    HxBoosted(boosted): HxBoosted,
+) -> impl IntoResponse {
+    let Some(Path(id)) = path else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let mut conn = state.pool.acquire().await.unwrap();
+    let Some(competition) = db::get_competition(&mut conn, id).await else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let body = markup::competition_page(&competition);
+    if boosted {
+        body.into_response()
+    } else {
+        markup::index(body).into_response()
+    }
+}
+
+async fn seasons_handler(
+    State(state): State<AppState>,
+    HxBoosted(boosted): HxBoosted,
+) -> impl IntoResponse {
+    let seasons = db::get_seasons(&mut state.pool.acquire().await.unwrap()).await;
+    let body = markup::seasons_page(&seasons);
+    if boosted {
+        body
+    } else {
+        markup::index(body)
+    }
+}
+
+#[derive(Deserialize)]
+struct StartSeasonForm {
+    name: String,
+}
+
+async fn start_season_handler(
+    State(state): State<AppState>,
+    Form(form): Form<StartSeasonForm>,
+) -> impl IntoResponse {
+    let mut tx = state.pool.begin().await.unwrap();
+    match db::start_season(&mut tx, &form.name).await {
+        Ok(season) => {
+            let matches = db::get_matches(&mut tx, season.id).await;
+            let seasons = db::get_seasons(&mut tx).await;
+            tx.commit().await.unwrap();
+            state
+                .clients
+                .send_to_clients(
+                    ClientView::MainPage,
+                    &Message::Text(markup::match_list(&matches).into_string()),
+                )
+                .await;
+            markup::season_list(&seasons).into_response()
+        }
+        Err(e) => markup::error(&e.to_string()).into_response(),
+    }
+}
+
+async fn season_handler(
+    State(state): State<AppState>,
+    path: Option<Path<i32>>,
+    HxBoosted(boosted): HxBoosted,
+) -> impl IntoResponse {
+    let Some(Path(id)) = path else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let mut conn = state.pool.acquire().await.unwrap();
+    let Some(season) = db::get_season(&mut conn, id).await else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let competitions = db::get_season_competitions(&mut conn, id).await;
+    let matches = db::get_matches(&mut conn, id).await;
+    let body = markup::season_page(&season, &competitions, &matches);
+    if boosted {
+        body.into_response()
+    } else {
+        markup::index(body).into_response()
+    }
+}
+
+async fn venues_handler(
+    State(state): State<AppState>,
+    HxBoosted(boosted): HxBoosted,
+) -> impl IntoResponse {
+    let venues = db::get_venues(&mut state.pool.acquire().await.unwrap()).await;
+    let body = markup::venues_page(&venues);
+    if boosted {
+        body
+    } else {
+        markup::index(body)
+    }
+}
+
+#[derive(Deserialize)]
+struct AddVenueForm {
+    name: String,
+    courts: String,
+    slot_minutes: String,
+}
+
+async fn add_venue_handler(
+    State(state): State<AppState>,
+    Form(form): Form<AddVenueForm>,
+) -> impl IntoResponse {
+    let mut tx = state.pool.begin().await.unwrap();
+    match db::add_venue(&mut tx, &form.name, &form.courts, &form.slot_minutes).await {
+        Ok(venue) => {
+            tx.commit().await.unwrap();
+            markup::add_venue_entry(&venue).into_response()
+        }
+        Err(e) => markup::error(&e.to_string()).into_response(),
+    }
+}
+
+async fn brackets_handler(
+    State(state): State<AppState>,
+    HxBoosted(boosted): HxBoosted,
+) -> impl IntoResponse {
+    let mut conn = state.pool.acquire().await.unwrap();
+    let body = markup::brackets_page(
+        &db::get_brackets(&mut conn).await,
+        &db::get_competitions(&mut conn).await,
+    );
+    if boosted {
+        body
+    } else {
+        markup::index(body)
+    }
+}
+
+#[derive(Deserialize)]
+struct AddBracketForm {
+    name: String,
+    teams: String,
+    first_round: String,
+    round_interval: String,
+    rules: String,
+    competition: String,
+}
+
+async fn add_bracket_handler(
+    State(state): State<AppState>,
+    Form(form): Form<AddBracketForm>,
+) -> impl IntoResponse {
+    let teams: Vec<&str> = form.teams.lines().collect();
+    let mut tx = state.pool.begin().await.unwrap();
+    match db::add_bracket(
+        &mut tx,
+        &form.name,
+        &teams,
+        &form.first_round,
+        &form.round_interval,
+        &form.rules,
+        &form.competition,
+    )
+    .await
+    {
+        Ok(bracket) => {
+            tx.commit().await.unwrap();
+            for match_info in bracket
+                .rounds
+                .iter()
+                .flatten()
+                .filter_map(|node| node.match_info.as_ref())
+            {
+                state
+                    .clients
+                    .send_to_clients(
+                        ClientView::MainPage,
+                        &Message::Text(markup::add_match_entry(match_info).into_string()),
+                    )
+                    .await;
+                send_team_update(&state, [match_info.team_a_id, match_info.team_b_id]).await;
+            }
+            markup::add_bracket_entry(&bracket).into_response()
+        }
+        Err(e) => markup::error(&e.to_string()).into_response(),
+    }
+}
+
+async fn bracket_handler(
+    State(state): State<AppState>,
+    path: Option<Path<i32>>,
+    HxBoosted(boosted): HxBoosted,
+) -> impl IntoResponse {
+    let Some(Path(id)) = path else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let Some(bracket) = db::get_bracket(&mut state.pool.acquire().await.unwrap(), id).await else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let body = markup::bracket_page(&bracket);
+    if boosted {
+        body.into_response()
+    } else {
+        markup::index(body).into_response()
+    }
+}
+
+/// Redraws the bracket of a knockout match, a next round match can only exist
+/// right after this one finished so main-page clients get it as a new entry
+async fn send_bracket_update(state: &AppState, match_info: &db::Match) {
+    let Some(bracket) =
+        db::get_match_bracket(&mut state.pool.acquire().await.unwrap(), match_info.id).await
+    else {
+        return;
+    };
+    state
+        .clients
+        .send_to_clients(
+            ClientView::Bracket(bracket.id),
+            &Message::Text(markup::bracket_view(&bracket).into_string()),
+        )
+        .await;
+    if match_info.status != db::MatchStatus::Finished {
+        return;
+    }
+    if let Some(next_match) = bracket.next_match(match_info.id) {
+        state
+            .clients
+            .send_to_clients(
+                ClientView::MainPage,
+                &Message::Text(markup::add_match_entry(next_match).into_string()),
+            )
+            .await;
+        send_team_update(state, [next_match.team_a_id, next_match.team_b_id]).await;
+    }
+}
+
+async fn tournaments_handler(
+    State(state): State<AppState>,
+    HxBoosted(boosted): HxBoosted,
+) -> impl IntoResponse {
+    let mut conn = state.pool.acquire().await.unwrap();
+    let body = markup::tournaments_page(
+        &db::get_tournaments(&mut conn).await,
+        &db::get_competitions(&mut conn).await,
+    );
+    if boosted {
+        body
+    } else {
+        markup::index(body)
+    }
+}
+
+#[derive(Deserialize)]
+struct AddTournamentForm {
+    name: String,
+    format: db::TournamentFormat,
+    teams: String,
+    rules: String,
+    competition: String,
+}
+
+async fn add_tournament_handler(
+    State(state): State<AppState>,
+    Form(form): Form<AddTournamentForm>,
+) -> impl IntoResponse {
+    let teams: Vec<&str> = form.teams.lines().collect();
+    let mut tx = state.pool.begin().await.unwrap();
+    match db::add_tournament(
+        &mut tx,
+        &form.name,
+        form.format,
+        &teams,
+        &form.rules,
+        &form.competition,
+    )
+    .await
+    {
+        Ok(tournament) => {
+            tx.commit().await.unwrap();
+            markup::add_tournament_entry(&tournament).into_response()
+        }
+        Err(e) => markup::error(&e.to_string()).into_response(),
+    }
+}
+
+async fn tournament_handler(
+    State(state): State<AppState>,
+    path: Option<Path<i32>>,
+    HxBoosted(boosted): HxBoosted,
+) -> impl IntoResponse {
+    let Some(Path(id)) = path else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let Some(tournament) = db::get_tournament(&mut state.pool.acquire().await.unwrap(), id).await
+    else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let body = markup::tournament_page(&tournament);
+    if boosted {
+        body.into_response()
+    } else {
+        markup::index(body).into_response()
+    }
+}
+
+#[derive(Deserialize)]
+struct NextRoundForm {
+    date: String,
+}
+
+async fn next_round_handler(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    Form(form): Form<NextRoundForm>,
+) -> impl IntoResponse {
+    let mut tx = state.pool.begin().await.unwrap();
+    match schedule::next_round(&mut tx, id, &form.date).await {
+        Ok(matches) => {
+            let tournament = db::get_tournament(&mut tx, id).await.unwrap();
+            tx.commit().await.unwrap();
+            for match_info in &matches {
+                state
+                    .clients
+                    .send_to_clients(
+                        ClientView::MainPage,
+                        &Message::Text(markup::add_match_entry(match_info).into_string()),
+                    )
+                    .await;
+                send_team_update(&state, [match_info.team_a_id, match_info.team_b_id]).await;
+            }
+            send_tournament_view(&state, &tournament).await;
+            StatusCode::OK.into_response()
+        }
+        Err(e) => markup::error(&e.to_string()).into_response(),
+    }
+}
+
+async fn send_tournament_view(state: &AppState, tournament: &db::Tournament) {
+    state
+        .clients
+        .send_to_clients(
+            ClientView::Tournament(tournament.id),
+            &Message::Text(
+                markup::tournament_view(tournament, &schedule::records(tournament)).into_string(),
+            ),
+        )
+        .await;
+}
+
+async fn send_tournament_update(state: &AppState, match_info: &db::Match) {
+    if let Some(tournament) =
+        db::get_match_tournament(&mut state.pool.acquire().await.unwrap(), match_info.id).await
+    {
+        send_tournament_view(state, &tournament).await;
+    }
+}
+
+async fn pool_plays_handler(
+    State(state): State<AppState>,
+    HxBoosted(boosted): HxBoosted,
+) -> impl IntoResponse {
+    let mut conn = state.pool.acquire().await.unwrap();
+    let body = markup::pool_plays_page(
+        &db::get_pool_plays(&mut conn).await,
+        &db::get_competitions(&mut conn).await,
+    );
+    if boosted {
+        body
+    } else {
+        markup::index(body)
+    }
+}
+
+#[derive(Deserialize)]
+struct AddPoolPlayForm {
+    name: String,
+    pools: String,
+    start: String,
+    round_interval: String,
+    advancing: String,
+    tie_breaker_1: db::TieBreaker,
+    tie_breaker_2: db::TieBreaker,
+    tie_breaker_3: db::TieBreaker,
+    tie_breaker_4: db::TieBreaker,
+    rules: String,
+    competition: String,
+}
+
+impl AddPoolPlayForm {
+    fn setup(&self) -> schedule::PoolPlaySetup<'_> {
+        let mut tie_breakers = Vec::new();
+        for tie_breaker in [
+            self.tie_breaker_1,
+            self.tie_breaker_2,
+            self.tie_breaker_3,
+            self.tie_breaker_4,
+        ] {
+            if !tie_breakers.contains(&tie_breaker) {
+                tie_breakers.push(tie_breaker);
+            }
+        }
+        schedule::PoolPlaySetup {
+            name: &self.name,
+            pools: self
+                .pools
+                .lines()
+                .map(|pool| {
+                    pool.split(',')
+                        .map(str::trim)
+                        .filter(|team| !team.is_empty())
+                        .collect::<Vec<_>>()
+                })
+                .filter(|pool| !pool.is_empty())
+                .collect(),
+            tie_breakers,
+            advancing: &self.advancing,
+            start: &self.start,
+            round_interval: &self.round_interval,
+            ruleset: &self.rules,
+            competition: &self.competition,
+        }
+    }
+}
+
+async fn add_pool_play_handler(
+    State(state): State<AppState>,
+    Form(form): Form<AddPoolPlayForm>,
+) -> impl IntoResponse {
+    let mut tx = state.pool.begin().await.unwrap();
+    match schedule::create_pool_play(&mut tx, &form.setup()).await {
+        Ok(pool_play) => {
+            tx.commit().await.unwrap();
+            for match_info in pool_play.pools.iter().flat_map(|pool| &pool.matches) {
+                state
+                    .clients
+                    .send_to_clients(
+                        ClientView::MainPage,
+                        &Message::Text(markup::add_match_entry(match_info).into_string()),
+                    )
+                    .await;
+                send_team_update(&state, [match_info.team_a_id, match_info.team_b_id]).await;
+            }
+            markup::add_pool_play_entry(&db::PoolPlayInfo {
+                id: pool_play.id,
+                name: pool_play.name,
+            })
+            .into_response()
+        }
+        Err(e) => markup::error(&e.to_string()).into_response(),
+    }
+}
+
+async fn pool_play_handler(
+    State(state): State<AppState>,
+    path: Option<Path<i32>>,
+    HxBoosted(boosted): HxBoosted,
+) -> impl IntoResponse {
+    let Some(Path(id)) = path else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let Some(pool_play) = db::get_pool_play(&mut state.pool.acquire().await.unwrap(), id).await
+    else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let body = markup::pool_play_page(&pool_play);
+    if boosted {
+        body.into_response()
+    } else {
+        markup::index(body).into_response()
+    }
+}
+
+#[derive(Deserialize)]
+struct PlayoffsForm {
+    first_round: String,
+    round_interval: String,
+    rules: String,
+}
+
+async fn create_playoffs_handler(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    Form(form): Form<PlayoffsForm>,
+) -> impl IntoResponse {
+    let mut tx = state.pool.begin().await.unwrap();
+    match schedule::create_playoffs(
+        &mut tx,
+        id,
+        &form.first_round,
+        &form.round_interval,
+        &form.rules,
+    )
+    .await
+    {
+        Ok(bracket) => {
+            let pool_play = db::get_pool_play(&mut tx, id).await.unwrap();
+            tx.commit().await.unwrap();
+            for match_info in bracket
+                .rounds
+                .iter()
+                .flatten()
+                .filter_map(|node| node.match_info.as_ref())
+            {
+                state
+                    .clients
+                    .send_to_clients(
+                        ClientView::MainPage,
+                        &Message::Text(markup::add_match_entry(match_info).into_string()),
+                    )
+                    .await;
+                send_team_update(&state, [match_info.team_a_id, match_info.team_b_id]).await;
+            }
+            state
+                .clients
+                .send_to_clients(
+                    ClientView::PoolPlay(id),
+                    &Message::Text(markup::pool_play_view(&pool_play).into_string()),
+                )
+                .await;
+            StatusCode::OK.into_response()
+        }
+        Err(e) => markup::error(&e.to_string()).into_response(),
+    }
+}
+
+async fn schedule_handler(
+    State(state): State<AppState>,
+    HxBoosted(boosted): HxBoosted,
+) -> impl IntoResponse {
+    let competitions = db::get_competitions(&mut state.pool.acquire().await.unwrap()).await;
+    let body = markup::schedule_page(&competitions);
+    if boosted {
+        body
+    } else {
+        markup::index(body)
+    }
+}
+
+#[derive(Deserialize)]
+struct ScheduleForm {
+    teams: String,
+    start: String,
+    rules: String,
+    competition: String,
+    double_round: Option<String>,
+    monday: Option<String>,
+    tuesday: Option<String>,
+    wednesday: Option<String>,
+    thursday: Option<String>,
+    friday: Option<String>,
+    saturday: Option<String>,
+    sunday: Option<String>,
+}
+
+impl ScheduleForm {
+    fn round_robin(&self) -> schedule::RoundRobin<'_> {
+        let days = [
+            (&self.monday, Weekday::Monday),
+            (&self.tuesday, Weekday::Tuesday),
+            (&self.wednesday, Weekday::Wednesday),
+            (&self.thursday, Weekday::Thursday),
+            (&self.friday, Weekday::Friday),
+            (&self.saturday, Weekday::Saturday),
+            (&self.sunday, Weekday::Sunday),
+        ];
+        schedule::RoundRobin {
+            teams: self
+                .teams
+                .lines()
+                .map(str::trim)
+                .filter(|team| !team.is_empty())
+                .collect(),
+            start: &self.start,
+            match_days: days
+                .into_iter()
+                .filter(|(checked, _)| checked.is_some())
+                .map(|(_, day)| day)
+                .collect(),
+            double_round: self.double_round.is_some(),
+            ruleset: &self.rules,
+            competition: &self.competition,
+        }
+    }
+}
+
+async fn schedule_preview_handler(
+    State(state): State<AppState>,
+    Form(form): Form<ScheduleForm>,
+) -> impl IntoResponse {
+    let mut tx = state.pool.begin().await.unwrap();
+    let rounds = schedule::generate(&mut tx, &form.round_robin()).await;
+    tx.rollback().await.unwrap();
+    match rounds {
+        Ok(rounds) => markup::schedule_preview(&rounds).into_response(),
+        Err(e) => markup::error(&e.to_string()).into_response(),
+    }
+}
+
+async fn schedule_create_handler(
+    State(state): State<AppState>,
+    Form(form): Form<ScheduleForm>,
+) -> impl IntoResponse {
+    let mut tx = state.pool.begin().await.unwrap();
+    match schedule::generate(&mut tx, &form.round_robin()).await {
+        Ok(rounds) => {
+            tx.commit().await.unwrap();
+            for match_info in rounds.iter().flatten() {
+                state
+                    .clients
+                    .send_to_clients(
+                        ClientView::MainPage,
+                        &Message::Text(markup::add_match_entry(match_info).into_string()),
+                    )
+                    .await;
+                send_team_update(&state, [match_info.team_a_id, match_info.team_b_id]).await;
+            }
+            markup::schedule_created(rounds.iter().flatten().count()).into_response()
+        }
+        Err(e) => markup::error(&e.to_string()).into_response(),
+    }
+}
+
+async fn send_team_update(state: &AppState, team_ids: [i32; 2]) {
+    let mut conn = state.pool.acquire().await.unwrap();
+    for team_id in team_ids {
+        let Some(team) = db::get_team(&mut conn, team_id).await else {
+            continue;
+        };
+        let matches = db::get_team_matches(&mut conn, team_id).await;
+        state
+            .clients
+            .send_to_clients(
+                ClientView::Team(team_id),
+                &Message::Text(markup::team_view(&team, &matches).into_string()),
+            )
+            .await;
+    }
+}
+
+async fn send_standings_update(state: &AppState, match_info: &db::Match) {
+    let mut conn = state.pool.acquire().await.unwrap();
+    if let Some(pool_play) = db::get_match_pool_play(&mut conn, match_info.id).await {
+        state
+            .clients
+            .send_to_clients(
+                ClientView::PoolPlay(pool_play.id),
+                &Message::Text(markup::pool_play_view(&pool_play).into_string()),
+            )
+            .await;
+    }
+    let Some(competition_id) = match_info.competition_id else {
+        return;
+    };
+    let matches = db::get_competition_matches(&mut conn, competition_id).await;
+    state
+        .clients
+        .send_to_clients(
+            ClientView::Competition(competition_id),
+            &Message::Text(
+                markup::standings_table(&standings::standings(&matches)).into_string(),
+            ),
+        )
+        .await;
+}
+
+async fn swap_teams_handler(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+) -> impl IntoResponse {
+    let mut tx = state.pool.begin().await.unwrap();
+    if db::swap_teams(&mut tx, id).await {
+        state
+            .clients
+            .send_to_clients(
+                ClientView::MatchPage(id),
+                &Message::Text(
+                    markup::match_page_update(&db::get_match(&mut tx, id).await).into_string(),
+                ),
+            )
+            .await;
+        tx.commit().await.unwrap();
+    }
+}
+
+async fn match_handler(
+    State(state): State<AppState>,
+    path: Option<Path<i32>>,
+    HxBoosted(boosted): HxBoosted,
+) -> impl IntoResponse {
+    let Some(Path(id)) = path else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let mut conn = state.pool.acquire().await.unwrap();
+    if !db::match_exists(&mut conn, id).await {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+    let match_info = db::get_match(&mut conn, id).await;
+    let tie = db::get_tie(&mut conn, id).await;
+    let candidates = match tie {
+        Some(_) => Vec::new(),
+        None => db::tie_candidates(&mut conn, &match_info).await,
+    };
+    let courts = db::get_courts(&mut conn).await;
+    let officials = db::get_officials(&mut conn).await;
+    let head_to_head = match match_info.status {
+        db::MatchStatus::Planned => {
+            db::get_head_to_head(&mut conn, [match_info.team_a_id, match_info.team_b_id]).await
+        }
+        _ => Vec::new(),
+    };
+    let body = markup::match_page(
+        &match_info,
+        tie.as_ref(),
+        &candidates,
+        &courts,
+        &officials,
+        &head_to_head,
+    );
+    if boosted {
+        body.into_response()
+    } else {
+        markup::index(body).into_response()
+    }
+}
+
+/// Team page by id, or by name for links typed by hand
+async fn team_handler(
+    State(state): State<AppState>,
+    path: Option<Path<String>>,
+    HxBoosted(boosted): HxBoosted,
+) -> impl IntoResponse {
+    let Some(Path(team)) = path else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let mut conn = state.pool.acquire().await.unwrap();
+    let team = match team.parse() {
+        Ok(id) => db::get_team(&mut conn, id).await,
+        Err(_) => db::find_team(&mut conn, &team).await,
+    };
+    let Some(team) = team else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let body = markup::team_page(&team);
+    if boosted {
+        body.into_response()
+    } else {
+        markup::index(body).into_response()
+    }
+}
+
+async fn player_handler(
+    State(state): State<AppState>,
+    path: Option<Path<i32>>,
+    HxBoosted(boosted): HxBoosted,
+) -> impl IntoResponse {
+    let Some(Path(id)) = path else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let mut conn = state.pool.acquire().await.unwrap();
+    let Some(player) = db::get_player(&mut conn, id).await else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let matches = db::get_team_matches(&mut conn, player.team_id).await;
+    let body = markup::player_page(&player, &matches);
+    if boosted {
+        body.into_response()
+    } else {
+        markup::index(body).into_response()
+    }
+}
+
+async fn breakdown_handler(
+    State(state): State<AppState>,
+    path: Option<Path<i32>>,
+    HxBoosted(boosted): HxBoosted,
+) -> impl IntoResponse {
+    let Some(Path(id)) = path else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let mut conn = state.pool.acquire().await.unwrap();
+    if !db::match_exists(&mut conn, id).await {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+    let body = markup::breakdown_page(&db::get_match(&mut conn, id).await);
+    if boosted {
+        body.into_response()
+    } else {
+        markup::index(body).into_response()
+    }
+}
+
+async fn remove_match_handler(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+) -> impl IntoResponse {
+    let mut conn = state.pool.acquire().await.unwrap();
+    if !db::match_exists(&mut conn, id).await {
+        return ().into_response();
+    }
+    if db::in_bracket(&mut conn, id).await {
+        return markup::error("A bracket match cannot be cancelled or deleted, record a forfeit instead").into_response();
+    }
+    let match_info = db::get_match(&mut conn, id).await;
+    if db::remove_match(&mut conn, id).await {
+        drop(conn);
+        state
+            .clients
+            .send_to_clients(
+                ClientView::MainPage,
+                &Message::Text(markup::remove_match_entry(id).into_string()),
+            )
+            .await;
+        state
+            .clients
+            .send_to_clients(
+                ClientView::MatchPage(id),
+                &Message::Text(markup::remove_match_page().into_string()),
+            )
+            .await;
+        send_team_update(&state, [match_info.team_a_id, match_info.team_b_id]).await;
+    }
+    ().into_response()
+}
+
+#[derive(Deserialize)]
+struct PointForm {
+    rally_type: Option<db::RallyType>,
+    player_id: Option<i32>,
+}
+
+/// A request without a form records an untagged point, a malformed form is rejected
+fn point_tags(
+    form: Result<Form<PointForm>, FormRejection>,
+) -> Result<(Option<db::RallyType>, Option<i32>), FormRejection> {
+    match form {
+        Ok(Form(form)) => Ok((form.rally_type, form.player_id)),
+        Err(FormRejection::InvalidFormContentType(_)) => Ok((None, None)),
+        Err(e) => Err(e),
+    }
+}
+
+async fn add_point_a_handler(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    form: Result<Form<PointForm>, FormRejection>,
+) -> impl IntoResponse {
+    let (rally_type, player_id) = match point_tags(form) {
+        Ok(tags) => tags,
+        Err(_) => return markup::error("Invalid rally type or player").into_response(),
+    };
+    let mut tx = state.pool.begin().await.unwrap();
+    if db::add_set_point(&mut tx, id, db::Team::A, rally_type, player_id).await {
+        let match_info = db::get_match(&mut tx, id).await;
+        state
+            .clients
+            .send_to_clients(
+                ClientView::MatchPage(id),
+                &Message::Text(markup::match_page_update(&match_info).into_string()),
+            )
+            .await;
+        tx.commit().await.unwrap();
+        countdown::sync(&state, &match_info).await;
+    }
+    ().into_response()
+}
+
+async fn add_point_b_handler(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    form: Result<Form<PointForm>, FormRejection>,
+) -> impl IntoResponse {
+    let (rally_type, player_id) = match point_tags(form) {
+        Ok(tags) => tags,
+        Err(_) => return markup::error("Invalid rally type or player").into_response(),
+    };
+    let mut tx = state.pool.begin().await.unwrap();
+    if db::add_set_point(&mut tx, id, db::Team::B, rally_type, player_id).await {
+        let match_info = db::get_match(&mut tx, id).await;
+        state
+            .clients
+            .send_to_clients(
+                ClientView::MatchPage(id),
+                &Message::Text(markup::match_page_update(&match_info).into_string()),
+            )
+            .await;
+        tx.commit().await.unwrap();
+        countdown::sync(&state, &match_info).await;
+    }
+    ().into_response()
+}
+
+async fn remove_point_a_handler(State(state): State<AppState>, Path(id): Path<i32>) {
+    let mut tx = state.pool.begin().await.unwrap();
+    if db::remove_set_point(&mut tx, id, db::Team::A).await {
+        let match_info = db::get_match(&mut tx, id).await;
+        state
+            .clients
+            .send_to_clients(
+                ClientView::MatchPage(id),
+                &Message::Text(markup::match_page_update(&match_info).into_string()),
+            )
+            .await;
+        tx.commit().await.unwrap();
+        countdown::sync(&state, &match_info).await;
+    }
+}
+
+async fn remove_point_b_handler(State(state): State<AppState>, Path(id): Path<i32>) {
+    let mut tx = state.pool.begin().await.unwrap();
+    if db::remove_set_point(&mut tx, id, db::Team::B).await {
+        let match_info = db::get_match(&mut tx, id).await;
+        state
+            .clients
+            .send_to_clients(
+                ClientView::MatchPage(id),
+                &Message::Text(markup::match_page_update(&match_info).into_string()),
+            )
+            .await;
+        tx.commit().await.unwrap();
+        countdown::sync(&state, &match_info).await;
+    }
+}
+
+async fn timeout_a_handler(State(state): State<AppState>, Path(id): Path<i32>) {
+    let mut tx = state.pool.begin().await.unwrap();
+    if db::take_timeout(&mut tx, id, db::Team::A).await {
+        let match_info = db::get_match(&mut tx, id).await;
+        tx.commit().await.unwrap();
+        state
+            .clients
+            .send_to_clients(
+                ClientView::MatchPage(id),
+                &Message::Text(markup::match_page_update(&match_info).into_string()),
+            )
+            .await;
+        countdown::sync(&state, &match_info).await;
+    }
+}
+
+async fn timeout_b_handler(State(state): State<AppState>, Path(id): Path<i32>) {
+    let mut tx = state.pool.begin().await.unwrap();
+    if db::take_timeout(&mut tx, id, db::Team::B).await {
+        let match_info = db::get_match(&mut tx, id).await;
+        tx.commit().await.unwrap();
+        state
+            .clients
+            .send_to_clients(
+                ClientView::MatchPage(id),
+                &Message::Text(markup::match_page_update(&match_info).into_string()),
+            )
+            .await;
+        countdown::sync(&state, &match_info).await;
+    }
+}
+
+#[derive(Deserialize)]
+struct SubstitutionForm {
+    team: db::Team,
+    player_in: String,
+    player_out: String,
+}
+
+async fn substitution_handler(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    Form(form): Form<SubstitutionForm>,
+) -> impl IntoResponse {
+    let mut tx = state.pool.begin().await.unwrap();
+    match db::substitute(&mut tx, id, form.team, &form.player_in, &form.player_out).await {
+        Ok(()) => {
+            let match_info = db::get_match(&mut tx, id).await;
+            tx.commit().await.unwrap();
+            state
+                .clients
+                .send_to_clients(
+                    ClientView::MatchPage(id),
+                    &Message::Text(markup::match_page_update(&match_info).into_string()),
+                )
+                .await;
+            ().into_response()
+        }
+        Err(e) => markup::error(&e.to_string()).into_response(),
+    }
+}
+
+#[derive(Deserialize)]
+struct SanctionForm {
+    team: db::Team,
+    player: String,
+    sanction: db::Sanction,
+}
+
+async fn sanction_handler(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    Form(form): Form<SanctionForm>,
+) -> impl IntoResponse {
+    let mut tx = state.pool.begin().await.unwrap();
+    match db::add_sanction(&mut tx, id, form.team, &form.player, form.sanction).await {
+        Ok(()) => {
+            let match_info = db::get_match(&mut tx, id).await;
+            tx.commit().await.unwrap();
+            state
+                .clients
+                .send_to_clients(
+                    ClientView::MatchPage(id),
+                    &Message::Text(markup::match_page_update(&match_info).into_string()),
+                )
+                .await;
+            countdown::sync(&state, &match_info).await;
+            ().into_response()
+        }
+        Err(e) => markup::error(&e.to_string()).into_response(),
+    }
+}
+
+#[derive(Deserialize)]
+struct PlayerForm {
+    team: db::Team,
+    number: String,
+    name: String,
+    role: db::PlayerRole,
+}
+
+async fn add_player_handler(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    Form(form): Form<PlayerForm>,
+) -> impl IntoResponse {
+    let mut tx = state.pool.begin().await.unwrap();
+    if !db::match_exists(&mut tx, id).await {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+    let match_info = db::get_match(&mut tx, id).await;
+    let team_id = match form.team {
+        db::Team::A => match_info.team_a_id,
+        db::Team::B => match_info.team_b_id,
+    };
+    match db::add_player(&mut tx, team_id, &form.number, &form.name, form.role).await {
+        Ok(()) => {
+            let match_info = db::get_match(&mut tx, id).await;
+            tx.commit().await.unwrap();
+            state
+                .clients
+                .send_to_clients(
+                    ClientView::MatchPage(id),
+                    &Message::Text(markup::match_page_update(&match_info).into_string()),
+                )
+                .await;
+            ().into_response()
+        }
+        Err(e) => markup::error(&e.to_string()).into_response(),
+    }
+}
+
+#[derive(Deserialize)]
+struct RemovePlayerForm {
+    player: i32,
+}
+
+async fn remove_player_handler(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    Form(form): Form<RemovePlayerForm>,
+) {
+    let mut tx = state.pool.begin().await.unwrap();
+    if db::remove_player(&mut tx, id, form.player).await {
+        let match_info = db::get_match(&mut tx, id).await;
+        tx.commit().await.unwrap();
+        state
+            .clients
+            .send_to_clients(
+                ClientView::MatchPage(id),
+                &Message::Text(markup::match_page_update(&match_info).into_string()),
+            )
+            .await;
+    }
+}
+
+#[derive(Deserialize)]
+struct HeadToHeadQuery {
+    #[serde(default)]
+    a: String,
+    #[serde(default)]
+    b: String,
+}
+
+async fn h2h_handler(
+    State(state): State<AppState>,
+    Query(query): Query<HeadToHeadQuery>,
+    HxBoosted(boosted): HxBoosted,
+) -> impl IntoResponse {
+    let mut conn = state.pool.acquire().await.unwrap();
+    let head_to_head = if query.a.trim().is_empty() || query.b.trim().is_empty() {
+        None
+    } else {
+        let team_a = db::find_team(&mut conn, &query.a).await;
+        let team_b = db::find_team(&mut conn, &query.b).await;
+        Some(match (team_a, team_b) {
+            (None, _) => Err(format!("Unknown team {}", query.a.trim())),
+            (_, None) => Err(format!("Unknown team {}", query.b.trim())),
+            (Some(team_a), Some(team_b)) if team_a.id == team_b.id => {
+                Err("Pick two different teams".to_owned())
+            }
+            (Some(team_a), Some(team_b)) => {
+                let meetings = db::get_head_to_head(&mut conn, [team_a.id, team_b.id]).await;
+                Ok(standings::head_to_head(meetings, [&team_a, &team_b]))
+            }
+        })
+    };
+    let team_names = db::get_team_names(&mut conn).await;
+    let body = markup::h2h_page(&team_names, &query.a, &query.b, head_to_head.as_ref());
+    if boosted {
+        body
+    } else {
+        markup::index(body)
+    }
+}
+
+async fn officials_handler(
+    State(state): State<AppState>,
+    HxBoosted(boosted): HxBoosted,
+) -> impl IntoResponse {
+    let officials = db::get_officials(&mut state.pool.acquire().await.unwrap()).await;
+    let body = markup::officials_page(&officials);
     if boosted {
         body
     } else {
@@ -34,166 +1248,336 @@ async fn index_handler(HxBoosted(boosted): HxBoosted) -> impl IntoResponse {
 }
 
 #[derive(Deserialize)]
-struct AddMatchForm {
-    date: String,
-    team_a: String,
-    team_b: String,
+struct AddOfficialForm {
+    name: String,
 }
 
-async fn add_match_handler(
+async fn add_official_handler(
     State(state): State<AppState>,
-    Form(form): Form<AddMatchForm>,
+    Form(form): Form<AddOfficialForm>,
 ) -> impl IntoResponse {
-    match db::add_match(
-        &mut state.pool.acquire().await.unwrap(),
-        &form.team_a,
-        &form.team_b,
-        &form.date,
-    )
-    .await
-    {
-        Ok(match_info) => {
+    let mut conn = state.pool.acquire().await.unwrap();
+    match db::add_official(&mut conn, &form.name).await {
+        Ok(official) => markup::add_official_entry(&official).into_response(),
+        Err(e) => markup::error(&e.to_string()).into_response(),
+    }
+}
+
+async fn official_handler(
+    State(state): State<AppState>,
+    path: Option<Path<i32>>,
+    HxBoosted(boosted): HxBoosted,
+) -> impl IntoResponse {
+    let Some(Path(id)) = path else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let mut conn = state.pool.acquire().await.unwrap();
+    let Some(official) = db::get_official(&mut conn, id).await else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let assignments = db::get_official_assignments(&mut conn, id).await;
+    let body = markup::official_page(&official, &assignments);
+    if boosted {
+        body.into_response()
+    } else {
+        markup::index(body).into_response()
+    }
+}
+
+#[derive(Deserialize)]
+struct AssignOfficialForm {
+    official: i32,
+    role: db::OfficialRole,
+}
+
+async fn assign_official_handler(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    Form(form): Form<AssignOfficialForm>,
+) -> impl IntoResponse {
+    let mut tx = state.pool.begin().await.unwrap();
+    match db::assign_official(&mut tx, id, form.official, form.role).await {
+        Ok(()) => {
+            let match_info = db::get_match(&mut tx, id).await;
+            tx.commit().await.unwrap();
             state
                 .clients
                 .send_to_clients(
-                    ClientView::MainPage,
-                    &Message::Text(markup::add_match_entry(&match_info).into_string()),
+                    ClientView::MatchPage(id),
+                    &Message::Text(markup::match_page_update(&match_info).into_string()),
                 )
                 .await;
             ().into_response()
         }
-        Err(e) => return markup::error(&e.to_string()).into_response(),
+        Err(e) => markup::error(&e.to_string()).into_response(),
     }
 }
 
-async fn swap_teams_handler(
+#[derive(Deserialize)]
+struct RemoveOfficialForm {
+    official: i32,
+}
+
+async fn remove_official_handler(
     State(state): State<AppState>,
     Path(id): Path<i32>,
-) -> impl IntoResponse {
+    Form(form): Form<RemoveOfficialForm>,
+) {
     let mut tx = state.pool.begin().await.unwrap();
-    if db::swap_teams(&mut *tx, id).await {
+    if db::remove_official(&mut tx, id, form.official).await {
+        let match_info = db::get_match(&mut tx, id).await;
+        tx.commit().await.unwrap();
         state
             .clients
             .send_to_clients(
                 ClientView::MatchPage(id),
-                &Message::Text(
-                    markup::match_page_update(&db::get_match(&mut *tx, id).await).into_string(),
-                ),
+                &Message::Text(markup::match_page_update(&match_info).into_string()),
             )
             .await;
-        tx.commit().await.unwrap();
     }
 }
 
-async fn match_handler(
-    State(state): State<AppState>,
-    path: Option<Path<i32>>,
-    HxBoosted(boosted): HxBoosted,
-) -> impl IntoResponse {
-    let Some(Path(id)) = path else {
-        return StatusCode::NOT_FOUND.into_response();
-    };
-    if !db::match_exists(&mut state.pool.acquire().await.unwrap(), id).await {
-        return StatusCode::NOT_FOUND.into_response();
-    }
-    let body = markup::match_page(id);
-    if boosted {
-        body.into_response()
-    } else {
-        markup::index(body).into_response()
-    }
+#[derive(Deserialize)]
+struct OutcomeForm {
+    team: db::Team,
 }
 
-async fn remove_match_handler(
+async fn forfeit_handler(
     State(state): State<AppState>,
     Path(id): Path<i32>,
-) -> impl IntoResponse {
-    if db::remove_match(&mut state.pool.acquire().await.unwrap(), id).await {
+    Form(form): Form<OutcomeForm>,
+) {
+    let mut tx = state.pool.begin().await.unwrap();
+    if db::forfeit(&mut tx, id, form.team).await {
+        let match_info = db::get_match(&mut tx, id).await;
+        let tie = db::get_tie(&mut tx, id).await;
+        tx.commit().await.unwrap();
         state
             .clients
             .send_to_clients(
-                ClientView::MainPage,
-                &Message::Text(markup::remove_match_entry(id).into_string()),
+                ClientView::MatchPage(id),
+                &Message::Text(markup::match_page_update(&match_info).into_string()),
             )
             .await;
         state
             .clients
             .send_to_clients(
-                ClientView::MatchPage(id),
-                &Message::Text(markup::remove_match_page().into_string()),
+                ClientView::MainPage,
+                &Message::Text(markup::update_match_entry(&match_info).into_string()),
             )
             .await;
+        send_team_update(&state, [match_info.team_a_id, match_info.team_b_id]).await;
+        countdown::sync(&state, &match_info).await;
+        send_tie_update(&state, tie).await;
+        send_standings_update(&state, &match_info).await;
+        send_bracket_update(&state, &match_info).await;
+        send_tournament_update(&state, &match_info).await;
     }
 }
 
-async fn add_point_a_handler(State(state): State<AppState>, Path(id): Path<i32>) {
+async fn retire_handler(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    Form(form): Form<OutcomeForm>,
+) {
     let mut tx = state.pool.begin().await.unwrap();
-    if db::add_set_point_a(&mut *tx, id).await {
+    if db::retire(&mut tx, id, form.team).await {
+        let match_info = db::get_match(&mut tx, id).await;
+        let tie = db::get_tie(&mut tx, id).await;
+        tx.commit().await.unwrap();
         state
             .clients
             .send_to_clients(
                 ClientView::MatchPage(id),
-                &Message::Text(
-                    markup::match_page_update(&db::get_match(&mut *tx, id).await).into_string(),
-                ),
+                &Message::Text(markup::match_page_update(&match_info).into_string()),
             )
             .await;
-        tx.commit().await.unwrap();
+        state
+            .clients
+            .send_to_clients(
+                ClientView::MainPage,
+                &Message::Text(markup::update_match_entry(&match_info).into_string()),
+            )
+            .await;
+        send_team_update(&state, [match_info.team_a_id, match_info.team_b_id]).await;
+        countdown::sync(&state, &match_info).await;
+        send_tie_update(&state, tie).await;
+        send_standings_update(&state, &match_info).await;
+        send_bracket_update(&state, &match_info).await;
+        send_tournament_update(&state, &match_info).await;
     }
 }
 
-async fn add_point_b_handler(State(state): State<AppState>, Path(id): Path<i32>) {
+#[derive(Deserialize)]
+struct StartMatchForm {
+    serve: db::Team,
+}
+
+async fn start_match_handler(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    Form(form): Form<StartMatchForm>,
+) {
     let mut tx = state.pool.begin().await.unwrap();
-    if db::add_set_point_b(&mut *tx, id).await {
+    if db::start_match(&mut tx, id, form.serve).await {
+        let match_info = db::get_match(&mut tx, id).await;
+        tx.commit().await.unwrap();
         state
             .clients
             .send_to_clients(
                 ClientView::MatchPage(id),
-                &Message::Text(
-                    markup::match_page_update(&db::get_match(&mut *tx, id).await).into_string(),
-                ),
+                &Message::Text(markup::match_page_update(&match_info).into_string()),
             )
             .await;
-        tx.commit().await.unwrap();
+        state
+            .clients
+            .send_to_clients(
+                ClientView::MainPage,
+                &Message::Text(markup::update_match_entry(&match_info).into_string()),
+            )
+            .await;
+        send_team_update(&state, [match_info.team_a_id, match_info.team_b_id]).await;
     }
 }
 
-async fn remove_point_a_handler(State(state): State<AppState>, Path(id): Path<i32>) {
+#[derive(Deserialize)]
+struct ChangeStatusForm {
+    status: db::MatchStatus,
+}
+
+async fn change_status_handler(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    Form(form): Form<ChangeStatusForm>,
+) -> impl IntoResponse {
     let mut tx = state.pool.begin().await.unwrap();
-    if db::remove_set_point_a(&mut tx, id).await {
+    if form.status == db::MatchStatus::Cancelled && db::in_bracket(&mut tx, id).await {
+        return markup::error("A bracket match cannot be cancelled or deleted, record a forfeit instead").into_response();
+    }
+    if db::change_status(&mut tx, id, form.status).await {
+        let match_info = db::get_match(&mut tx, id).await;
+        tx.commit().await.unwrap();
         state
             .clients
             .send_to_clients(
                 ClientView::MatchPage(id),
-                &Message::Text(
-                    markup::match_page_update(&db::get_match(&mut *tx, id).await).into_string(),
-                ),
+                &Message::Text(markup::match_page_update(&match_info).into_string()),
             )
             .await;
-        tx.commit().await.unwrap();
+        state
+            .clients
+            .send_to_clients(
+                ClientView::MainPage,
+                &Message::Text(markup::update_match_entry(&match_info).into_string()),
+            )
+            .await;
+        send_team_update(&state, [match_info.team_a_id, match_info.team_b_id]).await;
+        countdown::sync(&state, &match_info).await;
     }
+    ().into_response()
 }
 
-async fn remove_point_b_handler(State(state): State<AppState>, Path(id): Path<i32>) {
+#[derive(Deserialize)]
+struct AssignCourtForm {
+    court: String,
+}
+
+async fn assign_court_handler(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    Form(form): Form<AssignCourtForm>,
+) -> impl IntoResponse {
+    let mut tx = state.pool.begin().await.unwrap();
+    match db::assign_court(&mut tx, id, &form.court).await {
+        Ok(()) => {
+            let match_info = db::get_match(&mut tx, id).await;
+            tx.commit().await.unwrap();
+            state
+                .clients
+                .send_to_clients(
+                    ClientView::MainPage,
+                    &Message::Text(markup::update_match_entry(&match_info).into_string()),
+                )
+                .await;
+            send_team_update(&state, [match_info.team_a_id, match_info.team_b_id]).await;
+            ().into_response()
+        }
+        Err(e) => markup::error(&e.to_string()).into_response(),
+    }
+}
+
+#[derive(Deserialize)]
+struct RescheduleForm {
+    date: String,
+}
+
+async fn reschedule_handler(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    Form(form): Form<RescheduleForm>,
+) -> impl IntoResponse {
+    let mut tx = state.pool.begin().await.unwrap();
+    match db::reschedule(&mut tx, id, &form.date).await {
+        Ok(()) => {
+            let match_info = db::get_match(&mut tx, id).await;
+            tx.commit().await.unwrap();
+            state
+                .clients
+                .send_to_clients(
+                    ClientView::MatchPage(id),
+                    &Message::Text(markup::match_page_update(&match_info).into_string()),
+                )
+                .await;
+            state
+                .clients
+                .send_to_clients(
+                    ClientView::MainPage,
+                    &Message::Text(markup::update_match_entry(&match_info).into_string()),
+                )
+                .await;
+            send_team_update(&state, [match_info.team_a_id, match_info.team_b_id]).await;
+            ().into_response()
+        }
+        Err(e) => markup::error(&e.to_string()).into_response(),
+    }
+}
+
+async fn end_set_handler(State(state): State<AppState>, Path(id): Path<i32>) {
     let mut tx = state.pool.begin().await.unwrap();
-    if db::remove_set_point_b(&mut *tx, id).await {
+    if db::end_set(&mut tx, id).await {
+        let match_info = db::get_match(&mut tx, id).await;
+        let tie = db::get_tie(&mut tx, id).await;
+        tx.commit().await.unwrap();
         state
             .clients
             .send_to_clients(
                 ClientView::MatchPage(id),
-                &Message::Text(
-                    markup::match_page_update(&db::get_match(&mut *tx, id).await).into_string(),
-                ),
+                &Message::Text(markup::match_page_update(&match_info).into_string()),
             )
             .await;
-        tx.commit().await.unwrap();
+        state
+            .clients
+            .send_to_clients(
+                ClientView::MainPage,
+                &Message::Text(markup::update_match_entry(&match_info).into_string()),
+            )
+            .await;
+        send_team_update(&state, [match_info.team_a_id, match_info.team_b_id]).await;
+        countdown::sync(&state, &match_info).await;
+        send_tie_update(&state, tie).await;
+        if match_info.status == db::MatchStatus::Finished {
+            send_standings_update(&state, &match_info).await;
+        }
+        send_bracket_update(&state, &match_info).await;
+        send_tournament_update(&state, &match_info).await;
     }
 }
 
-async fn end_set_handler(State(state): State<AppState>, Path(id): Path<i32>) {
+async fn undo_handler(State(state): State<AppState>, Path(id): Path<i32>) {
     let mut tx = state.pool.begin().await.unwrap();
-    if db::end_set(&mut *tx, id).await {
-        let match_info = db::get_match(&mut *tx, id).await;
+    if db::undo(&mut tx, id).await {
+        let match_info = db::get_match(&mut tx, id).await;
+        let tie = db::get_tie(&mut tx, id).await;
         tx.commit().await.unwrap();
         state
             .clients
@@ -209,6 +1593,64 @@ async fn end_set_handler(State(state): State<AppState>, Path(id): Path<i32>) {
                 &Message::Text(markup::update_match_entry(&match_info).into_string()),
             )
             .await;
+        send_team_update(&state, [match_info.team_a_id, match_info.team_b_id]).await;
+        countdown::sync(&state, &match_info).await;
+        send_tie_update(&state, tie).await;
+        send_standings_update(&state, &match_info).await;
+        send_bracket_update(&state, &match_info).await;
+        send_tournament_update(&state, &match_info).await;
+    }
+}
+
+#[derive(Deserialize)]
+struct LinkTieForm {
+    other_leg: String,
+}
+
+async fn link_tie_handler(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    Form(form): Form<LinkTieForm>,
+) -> impl IntoResponse {
+    let mut tx = state.pool.begin().await.unwrap();
+    match db::link_tie(&mut tx, id, &form.other_leg).await {
+        Ok(()) => {
+            let tie = db::get_tie(&mut tx, id).await;
+            tx.commit().await.unwrap();
+            send_tie_update(&state, tie).await;
+            ().into_response()
+        }
+        Err(e) => markup::error(&e.to_string()).into_response(),
+    }
+}
+
+async fn golden_set_handler(State(state): State<AppState>, Path(id): Path<i32>) {
+    let mut tx = state.pool.begin().await.unwrap();
+    if let Some(golden_set) = db::create_golden_set(&mut tx, id).await {
+        let tie = db::get_tie(&mut tx, id).await;
+        tx.commit().await.unwrap();
+        state
+            .clients
+            .send_to_clients(
+                ClientView::MainPage,
+                &Message::Text(markup::add_match_entry(&golden_set).into_string()),
+            )
+            .await;
+        send_team_update(&state, [golden_set.team_a_id, golden_set.team_b_id]).await;
+        send_tie_update(&state, tie).await;
+    }
+}
+
+async fn send_tie_update(state: &AppState, tie: Option<db::Tie>) {
+    let Some(tie) = tie else {
+        return;
+    };
+    let message = Message::Text(markup::tie_panel(&tie).into_string());
+    for match_id in tie.match_ids() {
+        state
+            .clients
+            .send_to_clients(ClientView::MatchPage(match_id), &message)
+            .await;
     }
 }
 
@@ -219,6 +1661,46 @@ async fn ws_upgrade_handler(
     wsu.on_upgrade(|ws| ws::ws_handler(state, ws))
 }
 
+async fn bracket_ws_upgrade_handler(
+    Path(id): Path<i32>,
+    State(state): State<AppState>,
+    wsu: WebSocketUpgrade,
+) -> impl IntoResponse {
+    wsu.on_upgrade(move |ws| ws::bracket_ws_handler(id, state, ws))
+}
+
+async fn team_ws_upgrade_handler(
+    Path(id): Path<i32>,
+    State(state): State<AppState>,
+    wsu: WebSocketUpgrade,
+) -> impl IntoResponse {
+    wsu.on_upgrade(move |ws| ws::team_ws_handler(id, state, ws))
+}
+
+async fn tournament_ws_upgrade_handler(
+    Path(id): Path<i32>,
+    State(state): State<AppState>,
+    wsu: WebSocketUpgrade,
+) -> impl IntoResponse {
+    wsu.on_upgrade(move |ws| ws::tournament_ws_handler(id, state, ws))
+}
+
+async fn pool_play_ws_upgrade_handler(
+    Path(id): Path<i32>,
+    State(state): State<AppState>,
+    wsu: WebSocketUpgrade,
+) -> impl IntoResponse {
+    wsu.on_upgrade(move |ws| ws::pool_play_ws_handler(id, state, ws))
+}
+
+async fn competition_ws_upgrade_handler(
+    Path(id): Path<i32>,
+    State(state): State<AppState>,
+    wsu: WebSocketUpgrade,
+) -> impl IntoResponse {
+    wsu.on_upgrade(move |ws| ws::competition_ws_handler(id, state, ws))
+}
+
 async fn match_ws_upgrade_handler(
     Path(id): Path<i32>,
     State(state): State<AppState>,
@@ -231,6 +1713,11 @@ async fn match_ws_upgrade_handler(
 enum ClientView {
     MainPage,
     MatchPage(i32),
+    Competition(i32),
+    Bracket(i32),
+    Tournament(i32),
+    PoolPlay(i32),
+    Team(i32),
 }
 
 struct Client {
@@ -245,6 +1732,7 @@ struct ClientList(Arc<RwLock<HashMap<Uuid, Arc<Mutex<Client>>>>>);
 struct AppState {
     pool: PgPool,
     clients: ClientList,
+    countdowns: countdown::Countdowns,
 }
 
 impl ClientList {
@@ -269,18 +1757,71 @@ async fn main() {
         .route("/", get(index_handler))
         .route("/ws", get(ws_upgrade_handler))
         .route("/ws/:id", get(match_ws_upgrade_handler))
+        .route("/ws/competition/:id", get(competition_ws_upgrade_handler))
         .route("/add_match", post(add_match_handler))
         .route("/remove_match/:id", post(remove_match_handler))
         .route("/add_point_a/:id", post(add_point_a_handler))
         .route("/add_point_b/:id", post(add_point_b_handler))
         .route("/remove_point_a/:id", post(remove_point_a_handler))
         .route("/remove_point_b/:id", post(remove_point_b_handler))
+        .route("/timeout_a/:id", post(timeout_a_handler))
+        .route("/timeout_b/:id", post(timeout_b_handler))
+        .route("/substitution/:id", post(substitution_handler))
+        .route("/sanction/:id", post(sanction_handler))
+        .route("/add_player/:id", post(add_player_handler))
+        .route("/remove_player/:id", post(remove_player_handler))
+        .route("/assign_official/:id", post(assign_official_handler))
+        .route("/remove_official/:id", post(remove_official_handler))
+        .route("/officials", get(officials_handler))
+        .route("/h2h", get(h2h_handler))
+        .route("/add_official", post(add_official_handler))
+        .route("/official/:id", get(official_handler))
+        .route("/forfeit/:id", post(forfeit_handler))
+        .route("/retire/:id", post(retire_handler))
         .route("/swap_teams/:id", post(swap_teams_handler))
+        .route("/start_match/:id", post(start_match_handler))
+        .route("/change_status/:id", post(change_status_handler))
+        .route("/reschedule/:id", post(reschedule_handler))
+        .route("/assign_court/:id", post(assign_court_handler))
         .route("/end_set/:id", post(end_set_handler))
+        .route("/link_tie/:id", post(link_tie_handler))
+        .route("/golden_set/:id", post(golden_set_handler))
+        .route("/undo/:id", post(undo_handler))
         .route("/match/:id", get(match_handler))
+        .route("/competitions", get(competitions_handler))
+        .route("/add_competition", post(add_competition_handler))
+        .route("/competition/:id", get(competition_handler))
+        .route("/seasons", get(seasons_handler))
+        .route("/start_season", post(start_season_handler))
+        .route("/season/:id", get(season_handler))
+        .route("/venues", get(venues_handler))
+        .route("/add_venue", post(add_venue_handler))
+        .route("/brackets", get(brackets_handler))
+        .route("/add_bracket", post(add_bracket_handler))
+        .route("/bracket/:id", get(bracket_handler))
+        .route("/ws/bracket/:id", get(bracket_ws_upgrade_handler))
+        .route("/team/:team", get(team_handler))
+        .route("/ws/team/:id", get(team_ws_upgrade_handler))
+        .route("/tournaments", get(tournaments_handler))
+        .route("/add_tournament", post(add_tournament_handler))
+        .route("/tournament/:id", get(tournament_handler))
+        .route("/next_round/:id", post(next_round_handler))
+        .route("/ws/tournament/:id", get(tournament_ws_upgrade_handler))
+        .route("/pool_plays", get(pool_plays_handler))
+        .route("/add_pool_play", post(add_pool_play_handler))
+        .route("/pool_play/:id", get(pool_play_handler))
+        .route("/create_playoffs/:id", post(create_playoffs_handler))
+        .route("/ws/pool_play/:id", get(pool_play_ws_upgrade_handler))
+        .route("/schedule", get(schedule_handler))
+        .route("/schedule_preview", post(schedule_preview_handler))
+        .route("/schedule_create", post(schedule_create_handler))
+        .route("/breakdown/:id", get(breakdown_handler))
+        .route("/player/:id", get(player_handler))
+        .nest("/api/v1", api::router())
         .with_state(AppState {
             pool,
             clients: ClientList::default(),
+            countdowns: countdown::Countdowns::default(),
         });
     let listener = TcpListener::bind("0.0.0.0:".to_owned() + &env::var("PORT").unwrap())
         .await
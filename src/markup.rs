@@ -1,4 +1,9 @@
-use crate::db;
+use crate::{
+    db,
+    rules::{SetOutcome, PRESETS},
+    schedule,
+    standings::{self, Standing},
+};
 use maud::{html, Markup, PreEscaped, DOCTYPE};
 use time::format_description;
 
@@ -40,7 +45,12 @@ pub fn error(error_text: &str) -> Markup {
     }
 }
 
-pub fn main_page() -> Markup {
+pub fn main_page(
+    season: &db::Season,
+    team_names: &[String],
+    competitions: &[db::Competition],
+    courts: &[db::Court],
+) -> Markup {
     html! {
         (clipboard_def())
         div #error {}
@@ -56,29 +66,1096 @@ pub fn main_page() -> Markup {
                     div #toggle_finished .w-full.flex-initial.bg-sky-500.p-"[min(0.5rem,1vw)]".cursor-pointer.rounded-"[min(0.357rem,0.714vw)]"."hover:bg-sky-400"."active:bg-sky-300" {
                         "Finished"
                     }
+                    div #toggle_postponed .w-full.flex-initial.bg-sky-500.p-"[min(0.5rem,1vw)]".cursor-pointer.rounded-"[min(0.357rem,0.714vw)]"."hover:bg-sky-400"."active:bg-sky-300" {
+                        "Postponed"
+                    }
+                    div #toggle_suspended .w-full.flex-initial.bg-sky-500.p-"[min(0.5rem,1vw)]".cursor-pointer.rounded-"[min(0.357rem,0.714vw)]"."hover:bg-sky-400"."active:bg-sky-300" {
+                        "Suspended"
+                    }
+                    div #toggle_cancelled .w-full.flex-initial.bg-sky-500.p-"[min(0.5rem,1vw)]".cursor-pointer.rounded-"[min(0.357rem,0.714vw)]"."hover:bg-sky-400"."active:bg-sky-300" {
+                        "Cancelled"
+                    }
+                    a href="/competitions" hx-boost="true" .w-full.flex-initial.bg-zinc-700.p-"[min(0.5rem,1vw)]".cursor-pointer.rounded-"[min(0.357rem,0.714vw)]"."hover:bg-zinc-600"."active:bg-zinc-500" {
+                        "Competitions"
+                    }
+                    a href="/brackets" hx-boost="true" .w-full.flex-initial.bg-zinc-700.p-"[min(0.5rem,1vw)]".cursor-pointer.rounded-"[min(0.357rem,0.714vw)]"."hover:bg-zinc-600"."active:bg-zinc-500" {
+                        "Brackets"
+                    }
+                    a href="/tournaments" hx-boost="true" .w-full.flex-initial.bg-zinc-700.p-"[min(0.5rem,1vw)]".cursor-pointer.rounded-"[min(0.357rem,0.714vw)]"."hover:bg-zinc-600"."active:bg-zinc-500" {
+                        "Tournaments"
+                    }
+                    a href="/pool_plays" hx-boost="true" .w-full.flex-initial.bg-zinc-700.p-"[min(0.5rem,1vw)]".cursor-pointer.rounded-"[min(0.357rem,0.714vw)]"."hover:bg-zinc-600"."active:bg-zinc-500" {
+                        "Pools"
+                    }
+                    a href="/venues" hx-boost="true" .w-full.flex-initial.bg-zinc-700.p-"[min(0.5rem,1vw)]".cursor-pointer.rounded-"[min(0.357rem,0.714vw)]"."hover:bg-zinc-600"."active:bg-zinc-500" {
+                        "Venues"
+                    }
+                    a href="/officials" hx-boost="true" .w-full.flex-initial.bg-zinc-700.p-"[min(0.5rem,1vw)]".cursor-pointer.rounded-"[min(0.357rem,0.714vw)]"."hover:bg-zinc-600"."active:bg-zinc-500" {
+                        "Officials"
+                    }
+                    a href="/seasons" hx-boost="true" .w-full.flex-initial.bg-zinc-700.p-"[min(0.5rem,1vw)]".cursor-pointer.rounded-"[min(0.357rem,0.714vw)]"."hover:bg-zinc-600"."active:bg-zinc-500" {
+                        (season.name)
+                    }
+                }
+                div .bg-sky-500.flex.flex-none.items-center.rounded-"[min(0.357rem,0.714vw)]".py-"[min(1rem,2vw)]".px-"[min(0.5rem,1vw)]".gap-"[min(0.5rem,1vw)]" {
+                    div .w-full.flex-initial {"Team 1"}
+                    div .w-full.flex-initial {"Team 2"}
+                    div .w-full.flex-initial {"Date"}
+                    div .w-full.flex-initial {"Result"}
+                    div .w-full.flex-initial {"Status"}
+                    div .w-full.flex-initial {}
+                }
+            }
+            div #match_list .px-"[min(0.5rem,1vw)]".pb-"[min(4.5rem,9vw)]".pt-"[min(7.5rem,15vw)]".text-center.flex.flex-col.gap-"[min(0.5rem,1vw)]" {}
+            div #no_matches .px-"[min(0.5rem,1vw)]".pt-"[min(7.5rem,15vw)]".hidden {
+                div .bg-zinc-700.flex.justify-center.items-center.rounded-"[min(0.357rem,0.714vw)]".py-"[min(1rem,2vw)]" {"No matches"}
+            }
+            div .fixed.bottom-0.w-full.max-w-6xl.p-"[min(0.5rem,1vw)]".bg-zinc-800 {
+                form hx-post="/add_match" hx-swap="none" .grid.grid-cols-7.bg-zinc-700.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]".gap-"[min(0.5rem,1vw)]" {
+                    div .w-full.col-span-6.grid.grid-cols-6.gap-"[min(0.5rem,1vw)]".items-center {
+                        input type="text" name="team_a" list="teams" placeholder="Team 1 name" .p-"[min(0.5rem,1vw)]".placeholder-sky-500.outline-none."focus:outline-sky-500"."focus:outline-offset-0"."focus:outline"."focus:outline-[min(0.125rem,0.25vw)]".caret-sky-500.rounded-"[min(0.357rem,0.714vw)]".text-center.w-full.bg-zinc-800;
+                        input type="text" name="team_b" list="teams" placeholder="Team 2 name" .p-"[min(0.5rem,1vw)]".placeholder-sky-500.outline-none."focus:outline-sky-500"."focus:outline-offset-0"."focus:outline"."focus:outline-[min(0.125rem,0.25vw)]".caret-sky-500.rounded-"[min(0.357rem,0.714vw)]".text-center.w-full.bg-zinc-800;
+                        input type="text" name="date" placeholder="Match date" .p-"[min(0.5rem,1vw)]".placeholder-sky-500.outline-none."focus:outline-sky-500"."focus:outline-offset-0"."focus:outline"."focus:outline-[min(0.125rem,0.25vw)]".caret-sky-500.rounded-"[min(0.357rem,0.714vw)]".text-center.w-full.bg-zinc-800;
+                        datalist #teams {
+                            @for team_name in team_names {
+                                option value=(team_name) {}
+                            }
+                        }
+                        select name="rules" .p-"[min(0.5rem,1vw)]".text-sky-500.outline-none."focus:outline-sky-500"."focus:outline-offset-0"."focus:outline"."focus:outline-[min(0.125rem,0.25vw)]".rounded-"[min(0.357rem,0.714vw)]".text-center.w-full.bg-zinc-800 {
+                            @for (name, _) in PRESETS {
+                                option value=(name) {(name)}
+                            }
+                        }
+                        select name="competition" .p-"[min(0.5rem,1vw)]".text-sky-500.outline-none."focus:outline-sky-500"."focus:outline-offset-0"."focus:outline"."focus:outline-[min(0.125rem,0.25vw)]".rounded-"[min(0.357rem,0.714vw)]".text-center.w-full.bg-zinc-800 {
+                            option value="" {"No competition"}
+                            @for competition in competitions {
+                                option value=(competition.id) {(competition.name)}
+                            }
+                        }
+                        select name="court" .p-"[min(0.5rem,1vw)]".text-sky-500.outline-none."focus:outline-sky-500"."focus:outline-offset-0"."focus:outline"."focus:outline-[min(0.125rem,0.25vw)]".rounded-"[min(0.357rem,0.714vw)]".text-center.w-full.bg-zinc-800 {
+                            option value="" {"No court"}
+                            @for court in courts {
+                                option value=(court.id) {(court.name)}
+                            }
+                        }
+                    }
+                    div {
+                        input type="submit" value="Add" .w-full.bg-sky-500.p-"[min(0.5rem,1vw)]".cursor-pointer.rounded-"[min(0.357rem,0.714vw)]"."hover:bg-sky-400"."active:bg-sky-300"."focus:outline-none"."focus:bg-sky-400";
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub fn match_page(
+    match_info: &db::Match,
+    tie: Option<&db::Tie>,
+    tie_candidates: &[db::Match],
+    courts: &[db::Court],
+    officials: &[db::Official],
+    head_to_head: &[db::Match],
+) -> Markup {
+    html! {
+        div #error {}
+        div ws-connect={"/ws/" (match_info.id)} hx-on:":ws-after-message"="start_timer()" .h-screen.grid.justify-center.content-center {
+            div #score .bg-zinc-800.h-"[min(100vh,calc(9/16*100vw))]".w-"[min(100vw,calc(16/9*100vh))]" {
+            }
+        }
+        div .max-w-6xl.mx-auto.p-"[min(0.5rem,1vw)]".text-"[min(1rem,2vw)]".text-center.flex.flex-col.gap-"[min(0.5rem,1vw)]" {
+            div #players {}
+            @if match_info.archived {
+                a href={"/season/" (match_info.season_id)} hx-boost="true" .block.bg-zinc-700.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]"."hover:bg-zinc-600".text-amber-400 {"This match belongs to an archived season and is read-only"}
+            } @else {
+                (player_form(match_info))
+            }
+            div #officials {}
+            @if !match_info.archived {
+                (official_form(match_info, officials))
+                @if match_info.status == db::MatchStatus::Planned {
+                    (court_form(match_info, courts))
+                    (head_to_head_panel(match_info, head_to_head))
+                }
+            }
+            @if let Some(tie) = tie {
+                (tie_panel(tie))
+            } @else if !match_info.archived {
+                (tie_link_form(match_info, tie_candidates))
+            }
+            @if !match_info.archived {
+                (substitution_form(match_info))
+                (sanction_form(match_info))
+            }
+            a href={"/breakdown/" (match_info.id)} hx-boost="true" .block.bg-zinc-700.p-"[min(0.5rem,1vw)]".rounded-"[min(0.357rem,0.714vw)]"."hover:bg-zinc-600"."active:bg-zinc-500" {"Point breakdown"}
+            @if !match_info.archived {
+                (outcome_form(match_info))
+            }
+        }
+    }
+}
+
+fn head_to_head_panel(match_info: &db::Match, meetings: &[db::Match]) -> Markup {
+    let wins = |team_id: i32| {
+        meetings
+            .iter()
+            .filter(|meeting| {
+                let winner = if meeting.result[0] > meeting.result[1] {
+                    meeting.team_a_id
+                } else {
+                    meeting.team_b_id
+                };
+                winner == team_id
+            })
+            .count()
+    };
+    html! {
+        div .bg-zinc-800.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]".flex.flex-col.gap-"[min(0.5rem,1vw)]" {
+            div .bg-sky-500.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]" {"Head-to-head"}
+            @if meetings.is_empty() {
+                div .bg-zinc-700.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]" {"No previous meetings"}
+            } @else {
+                div .grid.grid-cols-3.bg-zinc-700.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]" {
+                    div .truncate {(match_info.team_a)}
+                    div {(wins(match_info.team_a_id)) " - " (wins(match_info.team_b_id))}
+                    div .truncate {(match_info.team_b)}
+                }
+                @for meeting in meetings {
+                    (meeting_row(meeting))
+                }
+            }
+            form action="/h2h" method="get" hx-boost="true" {
+                input type="hidden" name="a" value=(match_info.team_a);
+                input type="hidden" name="b" value=(match_info.team_b);
+                input type="submit" value="Full head-to-head" .w-full.bg-zinc-700.p-"[min(0.5rem,1vw)]".cursor-pointer.rounded-"[min(0.357rem,0.714vw)]"."hover:bg-zinc-600"."active:bg-zinc-500";
+            }
+        }
+    }
+}
+
+fn meeting_row(meeting: &db::Match) -> Markup {
+    let format = format_description::parse("[year].[month].[day]").unwrap();
+    let set_scores = meeting
+        .set_results_a
+        .iter()
+        .zip(&meeting.set_results_b)
+        .filter(|(a, b)| **a + **b > 0)
+        .map(|(a, b)| format!("{a}:{b}"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    html! {
+        a href={"/match/" (meeting.id)} hx-boost="true" .grid.grid-cols-4.gap-"[min(0.5rem,1vw)]".bg-zinc-700.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]"."hover:bg-zinc-600"."active:bg-zinc-500" {
+            div {(meeting.match_start.format(&format).unwrap())}
+            div .col-span-2.truncate {
+                (meeting.team_a) " " (meeting.result[0]) ":" (meeting.result[1]) " " (meeting.team_b)
+                @match meeting.outcome {
+                    Some(db::Outcome::Forfeit(_)) => span .text-amber-400 {" (forfeit)"},
+                    Some(db::Outcome::Retirement(_)) => span .text-amber-400 {" (retired)"},
+                    None => {},
+                }
+            }
+            div .truncate.text-zinc-400 {(set_scores)}
+        }
+    }
+}
+
+pub fn h2h_page(
+    team_names: &[String],
+    team_a: &str,
+    team_b: &str,
+    head_to_head: Option<&Result<standings::HeadToHead, String>>,
+) -> Markup {
+    html! {
+        div .max-w-6xl.mx-auto.p-"[min(0.5rem,1vw)]".text-"[min(1rem,2vw)]".text-center.flex.flex-col.gap-"[min(0.5rem,1vw)]" {
+            div .bg-sky-500.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]" {"Head-to-head"}
+            form action="/h2h" method="get" hx-boost="true" .grid.grid-cols-5.bg-zinc-700.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]".gap-"[min(0.5rem,1vw)]" {
+                input type="text" name="a" value=(team_a) list="teams" placeholder="Team 1 name" .col-span-2.p-"[min(0.5rem,1vw)]".placeholder-sky-500.outline-none."focus:outline-sky-500"."focus:outline-offset-0"."focus:outline"."focus:outline-[min(0.125rem,0.25vw)]".caret-sky-500.rounded-"[min(0.357rem,0.714vw)]".text-center.w-full.bg-zinc-800;
+                input type="text" name="b" value=(team_b) list="teams" placeholder="Team 2 name" .col-span-2.p-"[min(0.5rem,1vw)]".placeholder-sky-500.outline-none."focus:outline-sky-500"."focus:outline-offset-0"."focus:outline"."focus:outline-[min(0.125rem,0.25vw)]".caret-sky-500.rounded-"[min(0.357rem,0.714vw)]".text-center.w-full.bg-zinc-800;
+                datalist #teams {
+                    @for team_name in team_names {
+                        option value=(team_name) {}
+                    }
+                }
+                input type="submit" value="Compare" .w-full.bg-sky-500.p-"[min(0.5rem,1vw)]".cursor-pointer.rounded-"[min(0.357rem,0.714vw)]"."hover:bg-sky-400"."active:bg-sky-300"."focus:outline-none"."focus:bg-sky-400";
+            }
+            @match head_to_head {
+                Some(Ok(head_to_head)) => {
+                    div .bg-sky-500.flex.items-center.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]".gap-"[min(0.5rem,1vw)]" {
+                        div .w-full.flex-initial {"Team"}
+                        div .w-full.flex-initial {"Wins"}
+                        div .w-full.flex-initial {"Sets"}
+                        div .w-full.flex-initial {"Rally points"}
+                    }
+                    @for standing in &head_to_head.standings {
+                        div .bg-zinc-700.flex.items-center.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]".gap-"[min(0.5rem,1vw)]" {
+                            div .w-full.flex-initial.truncate {(standing.team)}
+                            div .w-full.flex-initial {(standing.won)}
+                            div .w-full.flex-initial {(standing.sets_won)}
+                            div .w-full.flex-initial {(standing.points_won)}
+                        }
+                    }
+                    @if head_to_head.meetings.is_empty() {
+                        div .bg-zinc-700.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]" {"No previous meetings"}
+                    }
+                    @for meeting in &head_to_head.meetings {
+                        (meeting_row(meeting))
+                    }
+                },
+                Some(Err(message)) => {
+                    div .bg-zinc-700.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]" {(message)}
+                },
+                None => {},
+            }
+            a href="/" hx-boost="true" .block.bg-sky-500.p-"[min(0.5rem,1vw)]".rounded-"[min(0.357rem,0.714vw)]"."hover:bg-sky-400"."active:bg-sky-300" {"Back"}
+        }
+    }
+}
+
+fn official_form(match_info: &db::Match, officials: &[db::Official]) -> Markup {
+    html! {
+        form hx-post={"/assign_official/" (match_info.id)} hx-swap="none" .bg-zinc-800.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]".flex.flex-col.gap-"[min(0.5rem,1vw)]" {
+            div .bg-sky-500.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]" {"Assign official"}
+            div .grid.grid-cols-4.gap-"[min(0.5rem,1vw)]" {
+                select name="official" .col-span-2.p-"[min(0.5rem,1vw)]".text-sky-500.outline-none."focus:outline-sky-500"."focus:outline"."focus:outline-[min(0.125rem,0.25vw)]".rounded-"[min(0.357rem,0.714vw)]".text-center.w-full.bg-zinc-700 {
+                    @for official in officials {
+                        option value=(official.id) {(official.name)}
+                    }
+                }
+                select name="role" .p-"[min(0.5rem,1vw)]".text-sky-500.outline-none."focus:outline-sky-500"."focus:outline"."focus:outline-[min(0.125rem,0.25vw)]".rounded-"[min(0.357rem,0.714vw)]".text-center.w-full.bg-zinc-700 {
+                    option value="FIRST_REFEREE" {"1st referee"}
+                    option value="SECOND_REFEREE" {"2nd referee"}
+                    option value="SCORER" {"Scorer"}
+                    option value="LINE_JUDGE" {"Line judge"}
+                }
+                input type="submit" value="Assign" .w-full.bg-sky-500.p-"[min(0.5rem,1vw)]".cursor-pointer.rounded-"[min(0.357rem,0.714vw)]"."hover:bg-sky-400"."active:bg-sky-300"."focus:outline-none"."focus:bg-sky-400";
+            }
+        }
+    }
+}
+
+fn officials_panel(match_info: &db::Match) -> Markup {
+    html! {
+        div #officials .bg-zinc-800.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]".flex.flex-col.gap-"[min(0.5rem,1vw)]" {
+            div .bg-sky-500.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]" {"Officials"}
+            @if match_info.officials.is_empty() {
+                div .bg-zinc-700.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]" {"No officials assigned"}
+            }
+            @for official in &match_info.officials {
+                div .flex.items-center.gap-"[min(0.5rem,1vw)]".bg-zinc-700.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]" {
+                    div .min-w-max.text-zinc-400 {(official_role_label(official.role))}
+                    a href={"/official/" (official.official_id)} hx-boost="true" .w-full.truncate.text-left."hover:text-sky-400" {(official.name)}
+                    div hx-post={"/remove_official/" (match_info.id)} hx-vals={r#"{"official":"# (official.official_id) "}"} hx-confirm={"Remove " (official.name) " from the match?"} hx-swap="none" .min-w-max.bg-zinc-800.px-"[min(0.5rem,1vw)]".cursor-pointer.rounded-"[min(0.357rem,0.714vw)]"."hover:bg-zinc-600"."active:bg-zinc-500" {"Remove"}
+                }
+            }
+        }
+    }
+}
+
+fn official_role_label(role: db::OfficialRole) -> &'static str {
+    match role {
+        db::OfficialRole::FirstReferee => "1st referee",
+        db::OfficialRole::SecondReferee => "2nd referee",
+        db::OfficialRole::Scorer => "Scorer",
+        db::OfficialRole::LineJudge => "Line judge",
+    }
+}
+
+pub fn officials_page(officials: &[db::Official]) -> Markup {
+    html! {
+        div #error {}
+        div .max-w-6xl.mx-auto.p-"[min(0.5rem,1vw)]".text-"[min(1rem,2vw)]".text-center.flex.flex-col.gap-"[min(0.5rem,1vw)]" {
+            div .bg-sky-500.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]" {"Officials"}
+            div #official_list .flex.flex-col.gap-"[min(0.5rem,1vw)]" {
+                @for official in officials {
+                    (official_entry(official))
+                }
+            }
+            form hx-post="/add_official" hx-swap="none" .grid.grid-cols-6.bg-zinc-700.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]".gap-"[min(0.5rem,1vw)]" {
+                input type="text" name="name" placeholder="Official name" .col-span-5.p-"[min(0.5rem,1vw)]".placeholder-sky-500.outline-none."focus:outline-sky-500"."focus:outline-offset-0"."focus:outline"."focus:outline-[min(0.125rem,0.25vw)]".caret-sky-500.rounded-"[min(0.357rem,0.714vw)]".text-center.w-full.bg-zinc-800;
+                input type="submit" value="Add" .w-full.bg-sky-500.p-"[min(0.5rem,1vw)]".cursor-pointer.rounded-"[min(0.357rem,0.714vw)]"."hover:bg-sky-400"."active:bg-sky-300"."focus:outline-none"."focus:bg-sky-400";
+            }
+            a href="/" hx-boost="true" .block.bg-sky-500.p-"[min(0.5rem,1vw)]".rounded-"[min(0.357rem,0.714vw)]"."hover:bg-sky-400"."active:bg-sky-300" {"Back"}
+        }
+    }
+}
+
+fn official_entry(official: &db::Official) -> Markup {
+    html! {
+        a href={"/official/" (official.id)} hx-boost="true" .block.truncate.bg-zinc-700.p-"[min(0.5rem,1vw)]".rounded-"[min(0.357rem,0.714vw)]"."hover:bg-zinc-600"."active:bg-zinc-500" {(official.name)}
+    }
+}
+
+pub fn add_official_entry(official: &db::Official) -> Markup {
+    html! {
+        div #official_list hx-swap-oob="beforeend" {
+            (official_entry(official))
+        }
+    }
+}
+
+pub fn official_page(
+    official: &db::Official,
+    assignments: &[(db::Match, db::OfficialRole)],
+) -> Markup {
+    let format = format_description::parse("[year].[month].[day] [hour]:[minute]").unwrap();
+    let cell = "bg-zinc-700 p-[min(0.5rem,1vw)] rounded-[min(0.357rem,0.714vw)]";
+    html! {
+        div .max-w-6xl.mx-auto.p-"[min(0.5rem,1vw)]".text-"[min(1rem,2vw)]".text-center.flex.flex-col.gap-"[min(0.5rem,1vw)]" {
+            div .bg-sky-500.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]".truncate {(official.name)}
+            div .grid.grid-cols-5.gap-"[min(0.5rem,1vw)]" {
+                @for header in ["Date", "Match", "Court", "Role", "Status"] {
+                    div .bg-sky-500.p-"[min(0.5rem,1vw)]".rounded-"[min(0.357rem,0.714vw)]" {(header)}
+                }
+                @for (match_info, role) in assignments {
+                    a href={"/match/" (match_info.id)} hx-boost="true" class={(cell) " hover:bg-zinc-600"} {(match_info.match_start.format(&format).unwrap())}
+                    div class={(cell) " truncate"} {(match_info.team_a) " - " (match_info.team_b)}
+                    div class={(cell) " truncate"} {(match_info.court.as_deref().unwrap_or("-"))}
+                    div class=(cell) {(official_role_label(*role))}
+                    div class=(cell) {(format!("{:?}", match_info.status))}
+                }
+            }
+            @if assignments.is_empty() {
+                div class=(cell) {"No upcoming assignments"}
+            }
+            a href="/officials" hx-boost="true" .block.bg-sky-500.p-"[min(0.5rem,1vw)]".rounded-"[min(0.357rem,0.714vw)]"."hover:bg-sky-400"."active:bg-sky-300" {"Back"}
+        }
+    }
+}
+
+fn court_form(match_info: &db::Match, courts: &[db::Court]) -> Markup {
+    html! {
+        form hx-post={"/assign_court/" (match_info.id)} hx-swap="none" .bg-zinc-800.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]".flex.flex-col.gap-"[min(0.5rem,1vw)]" {
+            div .bg-sky-500.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]" {"Court"}
+            div .grid.grid-cols-4.gap-"[min(0.5rem,1vw)]" {
+                select name="court" .col-span-3.p-"[min(0.5rem,1vw)]".text-sky-500.outline-none."focus:outline-sky-500"."focus:outline"."focus:outline-[min(0.125rem,0.25vw)]".rounded-"[min(0.357rem,0.714vw)]".text-center.w-full.bg-zinc-700 {
+                    option value="" {"No court"}
+                    @for court in courts {
+                        option value=(court.id) selected[match_info.court_id == Some(court.id)] {(court.name)}
+                    }
+                }
+                input type="submit" value="Assign" .w-full.bg-sky-500.p-"[min(0.5rem,1vw)]".cursor-pointer.rounded-"[min(0.357rem,0.714vw)]"."hover:bg-sky-400"."active:bg-sky-300"."focus:outline-none"."focus:bg-sky-400";
+            }
+        }
+    }
+}
+
+fn sanction_form(match_info: &db::Match) -> Markup {
+    html! {
+        form hx-post={"/sanction/" (match_info.id)} hx-swap="none" .bg-zinc-800.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]".flex.flex-col.gap-"[min(0.5rem,1vw)]" {
+            div .bg-sky-500.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]" {"Sanction"}
+            div .grid.grid-cols-4.gap-"[min(0.5rem,1vw)]" {
+                select name="team" .p-"[min(0.5rem,1vw)]".text-sky-500.outline-none."focus:outline-sky-500"."focus:outline"."focus:outline-[min(0.125rem,0.25vw)]".rounded-"[min(0.357rem,0.714vw)]".text-center.w-full.bg-zinc-700 {
+                    option value="A" {(match_info.team_a)}
+                    option value="B" {(match_info.team_b)}
+                }
+                input type="text" name="player" placeholder="Player (empty for team)" .p-"[min(0.5rem,1vw)]".placeholder-sky-500.outline-none."focus:outline-sky-500"."focus:outline-offset-0"."focus:outline"."focus:outline-[min(0.125rem,0.25vw)]".caret-sky-500.rounded-"[min(0.357rem,0.714vw)]".text-center.w-full.bg-zinc-700;
+                select name="sanction" .p-"[min(0.5rem,1vw)]".text-sky-500.outline-none."focus:outline-sky-500"."focus:outline"."focus:outline-[min(0.125rem,0.25vw)]".rounded-"[min(0.357rem,0.714vw)]".text-center.w-full.bg-zinc-700 {
+                    option value="WARNING" {"Warning (yellow)"}
+                    option value="PENALTY" {"Penalty (red)"}
+                    option value="EXPULSION" {"Expulsion"}
+                    option value="DISQUALIFICATION" {"Disqualification"}
+                }
+                input type="submit" value="Record" .w-full.bg-sky-500.p-"[min(0.5rem,1vw)]".cursor-pointer.rounded-"[min(0.357rem,0.714vw)]"."hover:bg-sky-400"."active:bg-sky-300"."focus:outline-none"."focus:bg-sky-400";
+            }
+        }
+    }
+}
+
+fn outcome_form(match_info: &db::Match) -> Markup {
+    html! {
+        div .bg-zinc-800.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]".flex.flex-col.gap-"[min(0.5rem,1vw)]" {
+            div .bg-sky-500.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]" {"Forfeit / retirement"}
+            div .grid.grid-cols-4.gap-"[min(0.5rem,1vw)]" {
+                @for (team, name) in [("A", &match_info.team_a), ("B", &match_info.team_b)] {
+                    div hx-post={"/forfeit/" (match_info.id)} hx-vals={r#"{"team":""# (team) r#""}"#} hx-confirm={(name) " forfeits the match?"} hx-swap="none" .truncate.bg-zinc-700.p-"[min(0.5rem,1vw)]".cursor-pointer.rounded-"[min(0.357rem,0.714vw)]"."hover:bg-zinc-600"."active:bg-zinc-500" {(name) " forfeits"}
+                    div hx-post={"/retire/" (match_info.id)} hx-vals={r#"{"team":""# (team) r#""}"#} hx-confirm={(name) " retires from the match?"} hx-swap="none" .truncate.bg-zinc-700.p-"[min(0.5rem,1vw)]".cursor-pointer.rounded-"[min(0.357rem,0.714vw)]"."hover:bg-zinc-600"."active:bg-zinc-500" {(name) " retires"}
+                }
+            }
+        }
+    }
+}
+
+fn player_form(match_info: &db::Match) -> Markup {
+    html! {
+        form hx-post={"/add_player/" (match_info.id)} hx-swap="none" .bg-zinc-800.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]".flex.flex-col.gap-"[min(0.5rem,1vw)]" {
+            div .bg-sky-500.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]" {"Add player to roster"}
+            div .grid.grid-cols-5.gap-"[min(0.5rem,1vw)]" {
+                select name="team" .p-"[min(0.5rem,1vw)]".text-sky-500.outline-none."focus:outline-sky-500"."focus:outline"."focus:outline-[min(0.125rem,0.25vw)]".rounded-"[min(0.357rem,0.714vw)]".text-center.w-full.bg-zinc-700 {
+                    option value="A" {(match_info.team_a)}
+                    option value="B" {(match_info.team_b)}
+                }
+                input type="text" name="number" placeholder="Shirt number" .p-"[min(0.5rem,1vw)]".placeholder-sky-500.outline-none."focus:outline-sky-500"."focus:outline-offset-0"."focus:outline"."focus:outline-[min(0.125rem,0.25vw)]".caret-sky-500.rounded-"[min(0.357rem,0.714vw)]".text-center.w-full.bg-zinc-700;
+                input type="text" name="name" placeholder="Player name" .p-"[min(0.5rem,1vw)]".placeholder-sky-500.outline-none."focus:outline-sky-500"."focus:outline-offset-0"."focus:outline"."focus:outline-[min(0.125rem,0.25vw)]".caret-sky-500.rounded-"[min(0.357rem,0.714vw)]".text-center.w-full.bg-zinc-700;
+                select name="role" .p-"[min(0.5rem,1vw)]".text-sky-500.outline-none."focus:outline-sky-500"."focus:outline"."focus:outline-[min(0.125rem,0.25vw)]".rounded-"[min(0.357rem,0.714vw)]".text-center.w-full.bg-zinc-700 {
+                    option value="SETTER" {"Setter"}
+                    option value="OUTSIDE_HITTER" {"Outside hitter"}
+                    option value="OPPOSITE" {"Opposite"}
+                    option value="MIDDLE_BLOCKER" {"Middle blocker"}
+                    option value="LIBERO" {"Libero"}
+                }
+                input type="submit" value="Add" .w-full.bg-sky-500.p-"[min(0.5rem,1vw)]".cursor-pointer.rounded-"[min(0.357rem,0.714vw)]"."hover:bg-sky-400"."active:bg-sky-300"."focus:outline-none"."focus:bg-sky-400";
+            }
+        }
+    }
+}
+
+fn players_panel(match_info: &db::Match) -> Markup {
+    html! {
+        div #players .grid.grid-cols-2.gap-"[min(0.5rem,1vw)]" {
+            @for team in [db::Team::A, db::Team::B] {
+                div .bg-zinc-800.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]".flex.flex-col.gap-"[min(0.5rem,1vw)]" {
+                    a href={"/team/" (match_info.team_id(team))} hx-boost="true" .truncate.bg-sky-500.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]"."hover:bg-sky-400" {(match_info.team_name(team))}
+                    @if match_info.rosters[team.index()].is_empty() {
+                        div .bg-zinc-700.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]" {"No players in the roster"}
+                    }
+                    @for player in &match_info.rosters[team.index()] {
+                        (player_row(match_info, team, player))
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn player_row(match_info: &db::Match, team: db::Team, player: &db::Player) -> Markup {
+    let path = match team {
+        db::Team::A => "/add_point_a/",
+        db::Team::B => "/add_point_b/",
+    };
+    let mut buttons = vec![("+1", None), ("ATK", Some("ATTACK"))];
+    if player.role != db::PlayerRole::Libero {
+        buttons.extend([("ACE", Some("ACE")), ("BLK", Some("BLOCK"))]);
+    }
+    html! {
+        div .flex.items-center.gap-"[min(0.5rem,1vw)]".bg-zinc-700.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]" {
+            a href={"/player/" (player.id)} hx-boost="true" .w-full.truncate.text-left."hover:text-sky-400" {
+                "#" (player.number) " " (player.name)
+                @if player.role == db::PlayerRole::Libero {
+                    " (L)"
+                }
+            }
+            div .min-w-max {(match_info.player_point_events(player.id).count()) " pts"}
+            @if match_info.status == db::MatchStatus::InProgress {
+                @for (label, rally_type) in buttons {
+                    div hx-post={(path) (match_info.id)} hx-vals={r#"{"player_id":"# (player.id) @if let Some(rally_type) = rally_type {r#","rally_type":""# (rally_type) r#"""#} "}"} hx-swap="none" .min-w-max.bg-zinc-800.px-"[min(0.5rem,1vw)]".cursor-pointer.rounded-"[min(0.357rem,0.714vw)]"."hover:bg-zinc-600"."active:bg-zinc-500" {(label)}
+                }
+            }
+            div hx-post={"/remove_player/" (match_info.id)} hx-vals={r#"{"player":"# (player.id) "}"} hx-confirm={"Remove #" (player.number) " " (player.name) " from the roster?"} hx-swap="none" .min-w-max.bg-zinc-800.px-"[min(0.5rem,1vw)]".cursor-pointer.rounded-"[min(0.357rem,0.714vw)]"."hover:bg-zinc-600"."active:bg-zinc-500" {"Remove"}
+        }
+    }
+}
+
+fn role_label(role: db::PlayerRole) -> &'static str {
+    match role {
+        db::PlayerRole::Setter => "Setter",
+        db::PlayerRole::OutsideHitter => "Outside hitter",
+        db::PlayerRole::Opposite => "Opposite",
+        db::PlayerRole::MiddleBlocker => "Middle blocker",
+        db::PlayerRole::Libero => "Libero",
+    }
+}
+
+pub fn team_page(team: &db::TeamInfo) -> Markup {
+    html! {
+        div .max-w-6xl.mx-auto.p-"[min(0.5rem,1vw)]".text-"[min(1rem,2vw)]".text-center.flex.flex-col.gap-"[min(0.5rem,1vw)]" {
+            div .bg-sky-500.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]".truncate {(team.name)}
+            div ws-connect={"/ws/team/" (team.id)} {
+                div #team_view {}
+            }
+            a href="/" hx-boost="true" .block.bg-sky-500.p-"[min(0.5rem,1vw)]".rounded-"[min(0.357rem,0.714vw)]"."hover:bg-sky-400"."active:bg-sky-300" {"Back"}
+        }
+    }
+}
+
+pub fn team_view(team: &db::TeamInfo, matches: &[db::Match]) -> Markup {
+    let record = standings::team_standing(matches, team);
+    let sections = [
+        ("Live", [db::MatchStatus::InProgress, db::MatchStatus::Suspended]),
+        ("Planned", [db::MatchStatus::Planned, db::MatchStatus::Postponed]),
+        ("Finished", [db::MatchStatus::Finished, db::MatchStatus::Cancelled]),
+    ];
+    let cell = "bg-zinc-700 p-[min(0.5rem,1vw)] rounded-[min(0.357rem,0.714vw)]";
+    html! {
+        div #team_view .flex.flex-col.gap-"[min(0.5rem,1vw)]" {
+            div .grid.grid-cols-5.gap-"[min(0.5rem,1vw)]" {
+                @for header in ["Played", "Won", "Lost", "Sets ratio", "Points ratio"] {
+                    div .bg-sky-500.p-"[min(0.5rem,1vw)]".rounded-"[min(0.357rem,0.714vw)]" {(header)}
+                }
+                div class=(cell) {(record.played)}
+                div class=(cell) {(record.won)}
+                div class=(cell) {(record.lost)}
+                div class=(cell) {(format_ratio(record.sets_ratio()))}
+                div class=(cell) {(format_ratio(record.points_ratio()))}
+            }
+            @for (title, statuses) in sections {
+                div .bg-sky-500.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]" {(title)}
+                @let section: Vec<_> = matches.iter().filter(|match_info| statuses.contains(&match_info.status)).collect();
+                @if section.is_empty() {
+                    div class=(cell) {"No matches"}
+                }
+                @for match_info in section {
+                    (match_entry(match_info))
+                }
+            }
+        }
+    }
+}
+
+pub fn player_page(player: &db::Player, matches: &[db::Match]) -> Markup {
+    let format = format_description::parse("[year].[month].[day] [hour]:[minute]").unwrap();
+    let cell = "bg-zinc-700 p-[min(0.5rem,1vw)] rounded-[min(0.357rem,0.714vw)]";
+    let count = |match_info: &db::Match, rally_type: Option<db::RallyType>| {
+        match_info
+            .player_point_events(player.id)
+            .filter(|event| rally_type.is_none() || event.rally_type == rally_type)
+            .count()
+    };
+    let columns = [
+        None,
+        Some(db::RallyType::Ace),
+        Some(db::RallyType::Attack),
+        Some(db::RallyType::Block),
+    ];
+    html! {
+        div .max-w-6xl.mx-auto.p-"[min(0.5rem,1vw)]".text-"[min(1rem,2vw)]".text-center.flex.flex-col.gap-"[min(0.5rem,1vw)]" {
+            div .bg-sky-500.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]".truncate {
+                "#" (player.number) " " (player.name) ", " (player.team) " (" (role_label(player.role)) ")"
+            }
+            div .grid.grid-cols-6.gap-"[min(0.5rem,1vw)]" {
+                @for header in ["Date", "Opponent", "Points", "Aces", "Attack kills", "Blocks"] {
+                    div .bg-sky-500.p-"[min(0.5rem,1vw)]".rounded-"[min(0.357rem,0.714vw)]" {(header)}
+                }
+                @for match_info in matches {
+                    a href={"/match/" (match_info.id)} hx-boost="true" class={(cell) " hover:bg-zinc-600"} {(match_info.match_start.format(&format).unwrap())}
+                    div class={(cell) " truncate"} {
+                        @if match_info.team_a_id == player.team_id {
+                            (match_info.team_b)
+                        } @else {
+                            (match_info.team_a)
+                        }
+                    }
+                    @for rally_type in columns {
+                        div class=(cell) {(count(match_info, rally_type))}
+                    }
+                }
+                div class=(cell) {"Total"}
+                div class=(cell) {"Matches: " (matches.len())}
+                @for rally_type in columns {
+                    div class=(cell) {(matches.iter().map(|match_info| count(match_info, rally_type)).sum::<usize>())}
+                }
+            }
+            a href="/" hx-boost="true" .block.bg-sky-500.p-"[min(0.5rem,1vw)]".rounded-"[min(0.357rem,0.714vw)]"."hover:bg-sky-400"."active:bg-sky-300" {"Back"}
+        }
+    }
+}
+
+fn tie_link_form(match_info: &db::Match, candidates: &[db::Match]) -> Markup {
+    let format = format_description::parse("[year].[month].[day] [hour]:[minute]").unwrap();
+    html! {
+        div #tie {
+            @if !candidates.is_empty() {
+                form hx-post={"/link_tie/" (match_info.id)} hx-swap="none" .bg-zinc-800.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]".flex.flex-col.gap-"[min(0.5rem,1vw)]" {
+                    div .bg-sky-500.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]" {"Two-legged tie"}
+                    div .grid.grid-cols-4.gap-"[min(0.5rem,1vw)]" {
+                        select name="other_leg" .col-span-3.p-"[min(0.5rem,1vw)]".text-sky-500.outline-none."focus:outline-sky-500"."focus:outline"."focus:outline-[min(0.125rem,0.25vw)]".rounded-"[min(0.357rem,0.714vw)]".text-center.w-full.bg-zinc-700 {
+                            @for candidate in candidates {
+                                option value=(candidate.id) {
+                                    (candidate.team_a) " - " (candidate.team_b) ", " (candidate.match_start.format(&format).unwrap())
+                                }
+                            }
+                        }
+                        input type="submit" value="Link legs" .w-full.bg-sky-500.p-"[min(0.5rem,1vw)]".cursor-pointer.rounded-"[min(0.357rem,0.714vw)]"."hover:bg-sky-400"."active:bg-sky-300"."focus:outline-none"."focus:bg-sky-400";
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub fn tie_panel(tie: &db::Tie) -> Markup {
+    html! {
+        div #tie .bg-zinc-800.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]".flex.flex-col.gap-"[min(0.5rem,1vw)]" {
+            div .bg-sky-500.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]" {"Two-legged tie"}
+            div .grid.grid-cols-3.gap-"[min(0.5rem,1vw)]" {
+                (tie_match("First leg", &tie.first_leg))
+                (tie_match("Second leg", &tie.second_leg))
+                @if let Some(golden_set) = &tie.golden_set {
+                    (tie_match("Golden set", golden_set))
+                } @else if tie.golden_set_needed() {
+                    div hx-post={"/golden_set/" (tie.first_leg.id)} hx-swap="none" .grid.content-center.bg-amber-500.animate-pulse.p-"[min(0.5rem,1vw)]".cursor-pointer.rounded-"[min(0.357rem,0.714vw)]"."hover:bg-amber-400"."active:bg-amber-300" {"Create golden set"}
+                } @else {
+                    div .grid.content-center.bg-zinc-900.p-"[min(0.5rem,1vw)]".rounded-"[min(0.357rem,0.714vw)]" {"No golden set"}
+                }
+            }
+            @if let Some(winner) = tie.winner() {
+                div .bg-zinc-700.text-amber-400.truncate.p-"[min(0.5rem,1vw)]".rounded-"[min(0.357rem,0.714vw)]" {"Tie winner: " (winner)}
+            }
+        }
+    }
+}
+
+fn tie_match(label: &str, match_info: &db::Match) -> Markup {
+    html! {
+        a href={"/match/" (match_info.id)} hx-boost="true" .block.truncate.bg-zinc-700.p-"[min(0.5rem,1vw)]".rounded-"[min(0.357rem,0.714vw)]"."hover:bg-zinc-600"."active:bg-zinc-500" {
+            div .text-sky-500 {(label)}
+            (match_info.team_a) " " (match_info.result[0]) ":" (match_info.result[1]) " " (match_info.team_b)
+        }
+    }
+}
+
+fn outcome_label(match_info: &db::Match) -> Option<String> {
+    match match_info.outcome? {
+        db::Outcome::Forfeit(db::Team::A) => Some(format!("Forfeit by {}", match_info.team_a)),
+        db::Outcome::Forfeit(db::Team::B) => Some(format!("Forfeit by {}", match_info.team_b)),
+        db::Outcome::Retirement(db::Team::A) => {
+            Some(format!("Retirement by {}", match_info.team_a))
+        }
+        db::Outcome::Retirement(db::Team::B) => {
+            Some(format!("Retirement by {}", match_info.team_b))
+        }
+    }
+}
+
+fn substitution_form(match_info: &db::Match) -> Markup {
+    html! {
+        form hx-post={"/substitution/" (match_info.id)} hx-swap="none" .bg-zinc-800.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]".flex.flex-col.gap-"[min(0.5rem,1vw)]" {
+            div .bg-sky-500.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]" {"Substitution"}
+            div .grid.grid-cols-4.gap-"[min(0.5rem,1vw)]" {
+                select name="team" .p-"[min(0.5rem,1vw)]".text-sky-500.outline-none."focus:outline-sky-500"."focus:outline"."focus:outline-[min(0.125rem,0.25vw)]".rounded-"[min(0.357rem,0.714vw)]".text-center.w-full.bg-zinc-700 {
+                    option value="A" {(match_info.team_a)}
+                    option value="B" {(match_info.team_b)}
+                }
+                input type="text" name="player_out" placeholder="Player out" .p-"[min(0.5rem,1vw)]".placeholder-sky-500.outline-none."focus:outline-sky-500"."focus:outline-offset-0"."focus:outline"."focus:outline-[min(0.125rem,0.25vw)]".caret-sky-500.rounded-"[min(0.357rem,0.714vw)]".text-center.w-full.bg-zinc-700;
+                input type="text" name="player_in" placeholder="Player in" .p-"[min(0.5rem,1vw)]".placeholder-sky-500.outline-none."focus:outline-sky-500"."focus:outline-offset-0"."focus:outline"."focus:outline-[min(0.125rem,0.25vw)]".caret-sky-500.rounded-"[min(0.357rem,0.714vw)]".text-center.w-full.bg-zinc-700;
+                input type="submit" value="Substitute" .w-full.bg-sky-500.p-"[min(0.5rem,1vw)]".cursor-pointer.rounded-"[min(0.357rem,0.714vw)]"."hover:bg-sky-400"."active:bg-sky-300"."focus:outline-none"."focus:bg-sky-400";
+            }
+        }
+    }
+}
+
+pub fn breakdown_page(match_info: &db::Match) -> Markup {
+    let cell = "bg-zinc-700 p-[min(0.5rem,1vw)] rounded-[min(0.357rem,0.714vw)]";
+    let rows = db::RallyType::ALL
+        .into_iter()
+        .map(|rally_type| (rally_type_label(rally_type), Some(rally_type)))
+        .chain([("Untagged", None)]);
+    html! {
+        div .max-w-6xl.mx-auto.p-"[min(0.5rem,1vw)]".text-"[min(1rem,2vw)]".text-center.flex.flex-col.gap-"[min(0.5rem,1vw)]" {
+            div .bg-sky-500.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]" {"Point breakdown"}
+            div .grid.grid-cols-3.gap-"[min(0.5rem,1vw)]" {
+                div {}
+                div .truncate.bg-sky-500.p-"[min(0.5rem,1vw)]".rounded-"[min(0.357rem,0.714vw)]" {(match_info.team_a)}
+                div .truncate.bg-sky-500.p-"[min(0.5rem,1vw)]".rounded-"[min(0.357rem,0.714vw)]" {(match_info.team_b)}
+                @for (label, rally_type) in rows {
+                    div class=(cell) {(label)}
+                    div class=(cell) {(match_info.rally_points(db::Team::A, rally_type))}
+                    div class=(cell) {(match_info.rally_points(db::Team::B, rally_type))}
+                }
+                @if match_info.outcome.is_some() {
+                    div class=(cell) {"Awarded"}
+                    div class=(cell) {(match_info.awarded_points(db::Team::A))}
+                    div class=(cell) {(match_info.awarded_points(db::Team::B))}
+                }
+                div class=(cell) {"Total"}
+                div class=(cell) {(match_info.set_results_a.iter().sum::<i32>())}
+                div class=(cell) {(match_info.set_results_b.iter().sum::<i32>())}
+            }
+            a href={"/match/" (match_info.id)} hx-boost="true" .block.bg-sky-500.p-"[min(0.5rem,1vw)]".rounded-"[min(0.357rem,0.714vw)]"."hover:bg-sky-400"."active:bg-sky-300" {"Back"}
+        }
+    }
+}
+
+pub fn competitions_page(competitions: &[db::Competition]) -> Markup {
+    html! {
+        div #error {}
+        div .max-w-6xl.mx-auto.p-"[min(0.5rem,1vw)]".text-"[min(1rem,2vw)]".text-center.flex.flex-col.gap-"[min(0.5rem,1vw)]" {
+            div .bg-sky-500.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]" {"Competitions"}
+            div #competition_list .flex.flex-col.gap-"[min(0.5rem,1vw)]" {
+                @for competition in competitions {
+                    (competition_entry(competition))
+                }
+            }
+            form hx-post="/add_competition" hx-swap="none" .grid.grid-cols-6.bg-zinc-700.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]".gap-"[min(0.5rem,1vw)]" {
+                input type="text" name="name" placeholder="Competition name" .col-span-5.p-"[min(0.5rem,1vw)]".placeholder-sky-500.outline-none."focus:outline-sky-500"."focus:outline-offset-0"."focus:outline"."focus:outline-[min(0.125rem,0.25vw)]".caret-sky-500.rounded-"[min(0.357rem,0.714vw)]".text-center.w-full.bg-zinc-800;
+                input type="submit" value="Add" .w-full.bg-sky-500.p-"[min(0.5rem,1vw)]".cursor-pointer.rounded-"[min(0.357rem,0.714vw)]"."hover:bg-sky-400"."active:bg-sky-300"."focus:outline-none"."focus:bg-sky-400";
+            }
+            a href="/schedule" hx-boost="true" .block.bg-zinc-700.p-"[min(0.5rem,1vw)]".rounded-"[min(0.357rem,0.714vw)]"."hover:bg-zinc-600"."active:bg-zinc-500" {"Generate schedule"}
+            a href="/" hx-boost="true" .block.bg-sky-500.p-"[min(0.5rem,1vw)]".rounded-"[min(0.357rem,0.714vw)]"."hover:bg-sky-400"."active:bg-sky-300" {"Back"}
+        }
+    }
+}
+
+pub fn seasons_page(seasons: &[db::Season]) -> Markup {
+    html! {
+        div #error {}
+        div .max-w-6xl.mx-auto.p-"[min(0.5rem,1vw)]".text-"[min(1rem,2vw)]".text-center.flex.flex-col.gap-"[min(0.5rem,1vw)]" {
+            div .bg-sky-500.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]" {"Seasons"}
+            (season_list(seasons))
+            form hx-post="/start_season" hx-swap="none" hx-confirm="Start a new season? Finished matches of the current season become read-only." .grid.grid-cols-6.bg-zinc-700.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]".gap-"[min(0.5rem,1vw)]" {
+                input type="text" name="name" placeholder="New season name" .col-span-5.p-"[min(0.5rem,1vw)]".placeholder-sky-500.outline-none."focus:outline-sky-500"."focus:outline-offset-0"."focus:outline"."focus:outline-[min(0.125rem,0.25vw)]".caret-sky-500.rounded-"[min(0.357rem,0.714vw)]".text-center.w-full.bg-zinc-800;
+                input type="submit" value="Start" .w-full.bg-sky-500.p-"[min(0.5rem,1vw)]".cursor-pointer.rounded-"[min(0.357rem,0.714vw)]"."hover:bg-sky-400"."active:bg-sky-300"."focus:outline-none"."focus:bg-sky-400";
+            }
+            a href="/" hx-boost="true" .block.bg-sky-500.p-"[min(0.5rem,1vw)]".rounded-"[min(0.357rem,0.714vw)]"."hover:bg-sky-400"."active:bg-sky-300" {"Back"}
+        }
+    }
+}
+
+pub fn season_list(seasons: &[db::Season]) -> Markup {
+    let format = format_description::parse("[year].[month].[day]").unwrap();
+    html! {
+        div #season_list hx-swap-oob="true" .flex.flex-col.gap-"[min(0.5rem,1vw)]" {
+            @for season in seasons {
+                a href={"/season/" (season.id)} hx-boost="true" .grid.grid-cols-3.bg-zinc-700.p-"[min(0.5rem,1vw)]".rounded-"[min(0.357rem,0.714vw)]"."hover:bg-zinc-600"."active:bg-zinc-500" {
+                    div .truncate {(season.name)}
+                    div {"Since " (season.started_at.format(&format).unwrap())}
+                    @if season.current {
+                        div .text-sky-400 {"Current"}
+                    } @else {
+                        div .text-zinc-400 {"Archived"}
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub fn season_page(
+    season: &db::Season,
+    competitions: &[db::Competition],
+    matches: &[db::Match],
+) -> Markup {
+    html! {
+        div .max-w-6xl.mx-auto.p-"[min(0.5rem,1vw)]".text-"[min(1rem,2vw)]".text-center.flex.flex-col.gap-"[min(0.5rem,1vw)]" {
+            div .bg-sky-500.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]".truncate {
+                (season.name)
+                @if !season.current {
+                    " (archived)"
+                }
+            }
+            @for competition in competitions {
+                (competition_entry(competition))
+            }
+            div .flex.flex-col.gap-"[min(0.5rem,1vw)]" {
+                @if matches.is_empty() {
+                    div .bg-zinc-700.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]" {"No matches"}
+                }
+                @for match_info in matches {
+                    (match_entry(match_info))
+                }
+            }
+            a href="/seasons" hx-boost="true" .block.bg-sky-500.p-"[min(0.5rem,1vw)]".rounded-"[min(0.357rem,0.714vw)]"."hover:bg-sky-400"."active:bg-sky-300" {"Back"}
+        }
+    }
+}
+
+pub fn venues_page(venues: &[db::Venue]) -> Markup {
+    html! {
+        div #error {}
+        div .max-w-6xl.mx-auto.p-"[min(0.5rem,1vw)]".text-"[min(1rem,2vw)]".text-center.flex.flex-col.gap-"[min(0.5rem,1vw)]" {
+            div .bg-sky-500.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]" {"Venues"}
+            div #venue_list .flex.flex-col.gap-"[min(0.5rem,1vw)]" {
+                @for venue in venues {
+                    (venue_entry(venue))
+                }
+            }
+            form hx-post="/add_venue" hx-swap="none" .grid.grid-cols-6.bg-zinc-700.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]".gap-"[min(0.5rem,1vw)]" {
+                input type="text" name="name" placeholder="Venue name" .col-span-3.p-"[min(0.5rem,1vw)]".placeholder-sky-500.outline-none."focus:outline-sky-500"."focus:outline-offset-0"."focus:outline"."focus:outline-[min(0.125rem,0.25vw)]".caret-sky-500.rounded-"[min(0.357rem,0.714vw)]".text-center.w-full.bg-zinc-800;
+                input type="text" name="courts" placeholder="Courts" .p-"[min(0.5rem,1vw)]".placeholder-sky-500.outline-none."focus:outline-sky-500"."focus:outline-offset-0"."focus:outline"."focus:outline-[min(0.125rem,0.25vw)]".caret-sky-500.rounded-"[min(0.357rem,0.714vw)]".text-center.w-full.bg-zinc-800;
+                input type="text" name="slot_minutes" placeholder="Slot minutes" .p-"[min(0.5rem,1vw)]".placeholder-sky-500.outline-none."focus:outline-sky-500"."focus:outline-offset-0"."focus:outline"."focus:outline-[min(0.125rem,0.25vw)]".caret-sky-500.rounded-"[min(0.357rem,0.714vw)]".text-center.w-full.bg-zinc-800;
+                input type="submit" value="Add" .w-full.bg-sky-500.p-"[min(0.5rem,1vw)]".cursor-pointer.rounded-"[min(0.357rem,0.714vw)]"."hover:bg-sky-400"."active:bg-sky-300"."focus:outline-none"."focus:bg-sky-400";
+            }
+            a href="/" hx-boost="true" .block.bg-sky-500.p-"[min(0.5rem,1vw)]".rounded-"[min(0.357rem,0.714vw)]"."hover:bg-sky-400"."active:bg-sky-300" {"Back"}
+        }
+    }
+}
+
+fn venue_entry(venue: &db::Venue) -> Markup {
+    html! {
+        div #{"venue_" (venue.id)} .grid.grid-cols-3.bg-zinc-700.p-"[min(0.5rem,1vw)]".rounded-"[min(0.357rem,0.714vw)]" {
+            div .truncate {(venue.name)}
+            div .truncate {(venue.courts.join(", "))}
+            div {(venue.slot_minutes) " min slots"}
+        }
+    }
+}
+
+pub fn add_venue_entry(venue: &db::Venue) -> Markup {
+    html! {
+        div #venue_list hx-swap-oob="beforeend" {
+            (venue_entry(venue))
+        }
+    }
+}
+
+pub fn schedule_page(competitions: &[db::Competition]) -> Markup {
+    const MATCH_DAYS: [(&str, &str); 7] = [
+        ("monday", "Mon"),
+        ("tuesday", "Tue"),
+        ("wednesday", "Wed"),
+        ("thursday", "Thu"),
+        ("friday", "Fri"),
+        ("saturday", "Sat"),
+        ("sunday", "Sun"),
+    ];
+    html! {
+        div #error {}
+        div .max-w-6xl.mx-auto.p-"[min(0.5rem,1vw)]".text-"[min(1rem,2vw)]".text-center.flex.flex-col.gap-"[min(0.5rem,1vw)]" {
+            div .bg-sky-500.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]" {"Round-robin schedule"}
+            form #schedule_form hx-post="/schedule_preview" hx-target="#schedule_preview" .grid.grid-cols-6.bg-zinc-700.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]".gap-"[min(0.5rem,1vw)]" {
+                textarea name="teams" rows="8" placeholder="One team per line" .col-span-6.p-"[min(0.5rem,1vw)]".placeholder-sky-500.outline-none."focus:outline-sky-500"."focus:outline-offset-0"."focus:outline"."focus:outline-[min(0.125rem,0.25vw)]".caret-sky-500.rounded-"[min(0.357rem,0.714vw)]".text-center.w-full.bg-zinc-800 {}
+                input type="text" name="start" placeholder="First match date" .col-span-2.p-"[min(0.5rem,1vw)]".placeholder-sky-500.outline-none."focus:outline-sky-500"."focus:outline-offset-0"."focus:outline"."focus:outline-[min(0.125rem,0.25vw)]".caret-sky-500.rounded-"[min(0.357rem,0.714vw)]".text-center.w-full.bg-zinc-800;
+                select name="rules" .col-span-2.p-"[min(0.5rem,1vw)]".text-sky-500.outline-none."focus:outline-sky-500"."focus:outline-offset-0"."focus:outline"."focus:outline-[min(0.125rem,0.25vw)]".rounded-"[min(0.357rem,0.714vw)]".text-center.w-full.bg-zinc-800 {
+                    @for (name, _) in PRESETS {
+                        option value=(name) {(name)}
+                    }
+                }
+                select name="competition" .col-span-2.p-"[min(0.5rem,1vw)]".text-sky-500.outline-none."focus:outline-sky-500"."focus:outline-offset-0"."focus:outline"."focus:outline-[min(0.125rem,0.25vw)]".rounded-"[min(0.357rem,0.714vw)]".text-center.w-full.bg-zinc-800 {
+                    option value="" {"No competition"}
+                    @for competition in competitions {
+                        option value=(competition.id) {(competition.name)}
+                    }
+                }
+                div .col-span-6.flex.flex-wrap.justify-center.gap-"[min(1rem,2vw)]" {
+                    @for (name, label) in MATCH_DAYS {
+                        label .flex.items-center.gap-"[min(0.25rem,0.5vw)]" {
+                            input type="checkbox" name=(name) .accent-sky-500;
+                            (label)
+                        }
+                    }
+                    label .flex.items-center.gap-"[min(0.25rem,0.5vw)]" {
+                        input type="checkbox" name="double_round" .accent-sky-500;
+                        "Home and away"
+                    }
+                }
+                input type="submit" value="Preview" .col-span-6.w-full.bg-sky-500.p-"[min(0.5rem,1vw)]".cursor-pointer.rounded-"[min(0.357rem,0.714vw)]"."hover:bg-sky-400"."active:bg-sky-300"."focus:outline-none"."focus:bg-sky-400";
+            }
+            div #schedule_preview .flex.flex-col.gap-"[min(0.5rem,1vw)]" {}
+            a href="/competitions" hx-boost="true" .block.bg-sky-500.p-"[min(0.5rem,1vw)]".rounded-"[min(0.357rem,0.714vw)]"."hover:bg-sky-400"."active:bg-sky-300" {"Back"}
+        }
+    }
+}
+
+pub fn schedule_preview(rounds: &[Vec<db::Match>]) -> Markup {
+    let format = format_description::parse("[year].[month].[day] [hour]:[minute]").unwrap();
+    let match_count: usize = rounds.iter().map(Vec::len).sum();
+    html! {
+        @for (round, matches) in rounds.iter().enumerate() {
+            div .bg-zinc-700.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]".flex.flex-col.gap-"[min(0.25rem,0.5vw)]" {
+                div .text-sky-500 {
+                    "Round " (round + 1)
+                    @if let Some(first) = matches.first() {
+                        " - " (first.match_start.format(&format).unwrap())
+                    }
+                }
+                @for match_info in matches {
+                    div .grid.grid-cols-3 {
+                        div .truncate {(match_info.team_a)}
+                        div {"vs"}
+                        div .truncate {(match_info.team_b)}
+                    }
+                }
+            }
+        }
+        button hx-post="/schedule_create" hx-include="#schedule_form" hx-target="#schedule_preview" .w-full.bg-sky-500.p-"[min(0.5rem,1vw)]".cursor-pointer.rounded-"[min(0.357rem,0.714vw)]"."hover:bg-sky-400"."active:bg-sky-300" {
+            "Create " (match_count) " matches"
+        }
+    }
+}
+
+pub fn schedule_created(match_count: usize) -> Markup {
+    html! {
+        div .bg-zinc-700.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]" {
+            "Created " (match_count) " matches"
+        }
+    }
+}
+
+fn competition_entry(competition: &db::Competition) -> Markup {
+    html! {
+        a href={"/competition/" (competition.id)} hx-boost="true" .block.truncate.bg-zinc-700.p-"[min(0.5rem,1vw)]".rounded-"[min(0.357rem,0.714vw)]"."hover:bg-zinc-600"."active:bg-zinc-500" {(competition.name)}
+    }
+}
+
+pub fn add_competition_entry(competition: &db::Competition) -> Markup {
+    html! {
+        div #competition_list hx-swap-oob="beforeend" {
+            (competition_entry(competition))
+        }
+    }
+}
+
+pub fn competition_page(competition: &db::Competition) -> Markup {
+    html! {
+        div .max-w-6xl.mx-auto.p-"[min(0.5rem,1vw)]".text-"[min(1rem,2vw)]".text-center.flex.flex-col.gap-"[min(0.5rem,1vw)]" {
+            div .bg-sky-500.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]".truncate {(competition.name)}
+            div ws-connect={"/ws/competition/" (competition.id)} {
+                div #standings {}
+            }
+            a href="/competitions" hx-boost="true" .block.bg-sky-500.p-"[min(0.5rem,1vw)]".rounded-"[min(0.357rem,0.714vw)]"."hover:bg-sky-400"."active:bg-sky-300" {"Back"}
+        }
+    }
+}
+
+fn format_ratio(ratio: f64) -> String {
+    if ratio.is_infinite() {
+        "MAX".to_owned()
+    } else {
+        format!("{ratio:.3}")
+    }
+}
+
+pub fn standings_table(standings: &[Standing]) -> Markup {
+    html! {
+        div #standings .flex.flex-col.gap-"[min(0.5rem,1vw)]" {
+            (standings_rows(standings))
+        }
+    }
+}
+
+fn standings_rows(standings: &[Standing]) -> Markup {
+    html! {
+        div .bg-sky-500.flex.items-center.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]".gap-"[min(0.5rem,1vw)]" {
+            div .w-full.flex-initial {"#"}
+            div .w-full.flex-initial {"Team"}
+            div .w-full.flex-initial {"Played"}
+            div .w-full.flex-initial {"Won"}
+            div .w-full.flex-initial {"Lost"}
+            div .w-full.flex-initial {"Points"}
+            div .w-full.flex-initial {"Sets"}
+            div .w-full.flex-initial {"Sets ratio"}
+            div .w-full.flex-initial {"Rally points"}
+            div .w-full.flex-initial {"Points ratio"}
+        }
+        @if standings.is_empty() {
+            div .bg-zinc-700.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]" {"No finished matches"}
+        }
+        @for (position, standing) in standings.iter().enumerate() {
+            div .bg-zinc-700.flex.items-center.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]".gap-"[min(0.5rem,1vw)]" {
+                div .w-full.flex-initial {(position + 1)}
+                div .w-full.flex-initial.truncate {(standing.team)}
+                div .w-full.flex-initial {(standing.played)}
+                div .w-full.flex-initial {(standing.won)}
+                div .w-full.flex-initial {(standing.lost)}
+                div .w-full.flex-initial {(standing.points)}
+                div .w-full.flex-initial {(standing.sets_won) ":" (standing.sets_lost)}
+                div .w-full.flex-initial {(format_ratio(standing.sets_ratio()))}
+                div .w-full.flex-initial {(standing.points_won) ":" (standing.points_lost)}
+                div .w-full.flex-initial {(format_ratio(standing.points_ratio()))}
+            }
+        }
+    }
+}
+
+pub fn brackets_page(brackets: &[db::BracketInfo], competitions: &[db::Competition]) -> Markup {
+    html! {
+        div #error {}
+        div .max-w-6xl.mx-auto.p-"[min(0.5rem,1vw)]".text-"[min(1rem,2vw)]".text-center.flex.flex-col.gap-"[min(0.5rem,1vw)]" {
+            div .bg-sky-500.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]" {"Brackets"}
+            div #bracket_list .flex.flex-col.gap-"[min(0.5rem,1vw)]" {
+                @for bracket in brackets {
+                    (bracket_entry(bracket.id, &bracket.name))
                 }
-                div .bg-sky-500.flex.flex-none.items-center.rounded-"[min(0.357rem,0.714vw)]".py-"[min(1rem,2vw)]".px-"[min(0.5rem,1vw)]".gap-"[min(0.5rem,1vw)]" {
-                    div .w-full.flex-initial {"Team 1"}
-                    div .w-full.flex-initial {"Team 2"}
-                    div .w-full.flex-initial {"Date"}
-                    div .w-full.flex-initial {"Result"}
-                    div .w-full.flex-initial {"Status"}
-                    div .w-full.flex-initial {}
+            }
+            form hx-post="/add_bracket" hx-swap="none" .grid.grid-cols-6.bg-zinc-700.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]".gap-"[min(0.5rem,1vw)]" {
+                input type="text" name="name" placeholder="Bracket name" .col-span-6.p-"[min(0.5rem,1vw)]".placeholder-sky-500.outline-none."focus:outline-sky-500"."focus:outline-offset-0"."focus:outline"."focus:outline-[min(0.125rem,0.25vw)]".caret-sky-500.rounded-"[min(0.357rem,0.714vw)]".text-center.w-full.bg-zinc-800;
+                textarea name="teams" rows="8" placeholder="One team per line, top seed first" .col-span-6.p-"[min(0.5rem,1vw)]".placeholder-sky-500.outline-none."focus:outline-sky-500"."focus:outline-offset-0"."focus:outline"."focus:outline-[min(0.125rem,0.25vw)]".caret-sky-500.rounded-"[min(0.357rem,0.714vw)]".text-center.w-full.bg-zinc-800 {}
+                input type="text" name="first_round" placeholder="First round date" .p-"[min(0.5rem,1vw)]".placeholder-sky-500.outline-none."focus:outline-sky-500"."focus:outline-offset-0"."focus:outline"."focus:outline-[min(0.125rem,0.25vw)]".caret-sky-500.rounded-"[min(0.357rem,0.714vw)]".text-center.w-full.bg-zinc-800;
+                input type="text" name="round_interval" placeholder="Days between rounds" .p-"[min(0.5rem,1vw)]".placeholder-sky-500.outline-none."focus:outline-sky-500"."focus:outline-offset-0"."focus:outline"."focus:outline-[min(0.125rem,0.25vw)]".caret-sky-500.rounded-"[min(0.357rem,0.714vw)]".text-center.w-full.bg-zinc-800;
+                select name="rules" .p-"[min(0.5rem,1vw)]".text-sky-500.outline-none."focus:outline-sky-500"."focus:outline-offset-0"."focus:outline"."focus:outline-[min(0.125rem,0.25vw)]".rounded-"[min(0.357rem,0.714vw)]".text-center.w-full.bg-zinc-800 {
+                    @for (name, _) in PRESETS {
+                        option value=(name) {(name)}
+                    }
+                }
+                select name="competition" .col-span-2.p-"[min(0.5rem,1vw)]".text-sky-500.outline-none."focus:outline-sky-500"."focus:outline-offset-0"."focus:outline"."focus:outline-[min(0.125rem,0.25vw)]".rounded-"[min(0.357rem,0.714vw)]".text-center.w-full.bg-zinc-800 {
+                    option value="" {"No competition"}
+                    @for competition in competitions {
+                        option value=(competition.id) {(competition.name)}
+                    }
                 }
+                input type="submit" value="Create" .w-full.bg-sky-500.p-"[min(0.5rem,1vw)]".cursor-pointer.rounded-"[min(0.357rem,0.714vw)]"."hover:bg-sky-400"."active:bg-sky-300"."focus:outline-none"."focus:bg-sky-400";
             }
-            div #match_list .px-"[min(0.5rem,1vw)]".pb-"[min(4.5rem,9vw)]".pt-"[min(7.5rem,15vw)]".text-center.flex.flex-col.gap-"[min(0.5rem,1vw)]" {}
-            div #no_matches .px-"[min(0.5rem,1vw)]".pt-"[min(7.5rem,15vw)]".hidden {
-                div .bg-zinc-700.flex.justify-center.items-center.rounded-"[min(0.357rem,0.714vw)]".py-"[min(1rem,2vw)]" {"No matches"}
+            a href="/" hx-boost="true" .block.bg-sky-500.p-"[min(0.5rem,1vw)]".rounded-"[min(0.357rem,0.714vw)]"."hover:bg-sky-400"."active:bg-sky-300" {"Back"}
+        }
+    }
+}
+
+fn bracket_entry(id: i32, name: &str) -> Markup {
+    html! {
+        a href={"/bracket/" (id)} hx-boost="true" .block.truncate.bg-zinc-700.p-"[min(0.5rem,1vw)]".rounded-"[min(0.357rem,0.714vw)]"."hover:bg-zinc-600"."active:bg-zinc-500" {(name)}
+    }
+}
+
+pub fn add_bracket_entry(bracket: &db::Bracket) -> Markup {
+    html! {
+        div #bracket_list hx-swap-oob="beforeend" {
+            (bracket_entry(bracket.id, &bracket.name))
+        }
+    }
+}
+
+pub fn bracket_page(bracket: &db::Bracket) -> Markup {
+    html! {
+        div .max-w-6xl.mx-auto.p-"[min(0.5rem,1vw)]".text-"[min(1rem,2vw)]".text-center.flex.flex-col.gap-"[min(0.5rem,1vw)]" {
+            div .bg-sky-500.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]".truncate {(bracket.name)}
+            div ws-connect={"/ws/bracket/" (bracket.id)} {
+                div #bracket {}
             }
-            div .fixed.bottom-0.w-full.max-w-6xl.p-"[min(0.5rem,1vw)]".bg-zinc-800 {
-                form hx-post="/add_match" hx-swap="none" .grid.grid-cols-6.bg-zinc-700.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]".gap-"[min(0.5rem,1vw)]" {
-                    div .w-full.col-span-5.grid.grid-cols-3.gap-"[min(0.5rem,1vw)]".items-center {
-                        input type="text" name="team_a" placeholder="Team 1 name" .p-"[min(0.5rem,1vw)]".placeholder-sky-500.outline-none."focus:outline-sky-500"."focus:outline-offset-0"."focus:outline"."focus:outline-[min(0.125rem,0.25vw)]".caret-sky-500.rounded-"[min(0.357rem,0.714vw)]".text-center.w-full.bg-zinc-800;
-                        input type="text" name="team_b" placeholder="Team 2 name" .p-"[min(0.5rem,1vw)]".placeholder-sky-500.outline-none."focus:outline-sky-500"."focus:outline-offset-0"."focus:outline"."focus:outline-[min(0.125rem,0.25vw)]".caret-sky-500.rounded-"[min(0.357rem,0.714vw)]".text-center.w-full.bg-zinc-800;
-                        input type="text" name="date" placeholder="Match date" .p-"[min(0.5rem,1vw)]".placeholder-sky-500.outline-none."focus:outline-sky-500"."focus:outline-offset-0"."focus:outline"."focus:outline-[min(0.125rem,0.25vw)]".caret-sky-500.rounded-"[min(0.357rem,0.714vw)]".text-center.w-full.bg-zinc-800;
+            a href="/brackets" hx-boost="true" .block.bg-sky-500.p-"[min(0.5rem,1vw)]".rounded-"[min(0.357rem,0.714vw)]"."hover:bg-sky-400"."active:bg-sky-300" {"Back"}
+        }
+    }
+}
+
+fn round_name(round: usize, rounds: usize) -> String {
+    match rounds - round {
+        1 => "Final".to_owned(),
+        2 => "Semifinals".to_owned(),
+        3 => "Quarterfinals".to_owned(),
+        left => format!("Round of {}", 1 << left),
+    }
+}
+
+pub fn bracket_view(bracket: &db::Bracket) -> Markup {
+    html! {
+        div #bracket .flex.flex-col.gap-"[min(0.5rem,1vw)]" {
+            div .flex.gap-"[min(0.5rem,1vw)]" {
+                @for (round, nodes) in bracket.rounds.iter().enumerate() {
+                    div .w-full.flex-initial.flex.flex-col.justify-around.gap-"[min(0.5rem,1vw)]" {
+                        div .bg-sky-500.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]" {(round_name(round, bracket.rounds.len()))}
+                        @for node in nodes {
+                            (bracket_node(node))
+                        }
                     }
-                    div {
-                        input type="submit" value="Add" .w-full.bg-sky-500.p-"[min(0.5rem,1vw)]".cursor-pointer.rounded-"[min(0.357rem,0.714vw)]"."hover:bg-sky-400"."active:bg-sky-300"."focus:outline-none"."focus:bg-sky-400";
+                }
+            }
+            @if let Some(champion) = bracket.champion() {
+                div .bg-amber-400.text-zinc-800.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]".truncate {"Winner: " (champion)}
+            }
+        }
+    }
+}
+
+fn bracket_node(node: &db::BracketNode) -> Markup {
+    let winner = node.winner();
+    html! {
+        div hx-get=[node.match_info.as_ref().map(|match_info| format!("/match/{}", match_info.id))] hx-push-url="true" .bg-zinc-700.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]".flex.flex-col.gap-"[min(0.25rem,0.5vw)]".cursor-pointer."hover:bg-zinc-600" {
+            @for side in 0..2 {
+                @let team = node.teams[side].as_deref();
+                div .flex.gap-"[min(0.5rem,1vw)]" .text-amber-400[team.is_some() && team == winner] {
+                    div .w-"[min(1.5rem,3vw)]".flex-none.text-zinc-400 {
+                        @if let Some(seed) = node.seeds[side] {
+                            (seed)
+                        }
+                    }
+                    div .w-full.flex-initial.truncate.text-left {
+                        @match team {
+                            Some(team) => (team),
+                            None if node.bye => span .text-zinc-400 {"Bye"},
+                            None => span .text-zinc-400 {"TBD"},
+                        }
+                    }
+                    @if let Some(match_info) = &node.match_info {
+                        @if match_info.status != db::MatchStatus::Planned {
+                            div .flex-none {(match_info.result[side])}
+                        }
                     }
                 }
             }
@@ -86,10 +1163,258 @@ pub fn main_page() -> Markup {
     }
 }
 
-pub fn match_page(match_id: i32) -> Markup {
+fn format_label(format: db::TournamentFormat) -> &'static str {
+    match format {
+        db::TournamentFormat::DoubleElimination => "Double elimination",
+        db::TournamentFormat::Swiss => "Swiss",
+    }
+}
+
+pub fn tournaments_page(
+    tournaments: &[db::TournamentInfo],
+    competitions: &[db::Competition],
+) -> Markup {
     html! {
-        div ws-connect={"/ws/" (match_id)} hx-on:":ws-after-message"="start_timer()" .h-screen.grid.justify-center.content-center {
-            div #score .bg-zinc-800.h-"[min(100vh,calc(9/16*100vw))]".w-"[min(100vw,calc(16/9*100vh))]" {
+        div #error {}
+        div .max-w-6xl.mx-auto.p-"[min(0.5rem,1vw)]".text-"[min(1rem,2vw)]".text-center.flex.flex-col.gap-"[min(0.5rem,1vw)]" {
+            div .bg-sky-500.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]" {"Tournaments"}
+            div #tournament_list .flex.flex-col.gap-"[min(0.5rem,1vw)]" {
+                @for tournament in tournaments {
+                    (tournament_entry(tournament))
+                }
+            }
+            form hx-post="/add_tournament" hx-swap="none" .grid.grid-cols-6.bg-zinc-700.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]".gap-"[min(0.5rem,1vw)]" {
+                input type="text" name="name" placeholder="Tournament name" .col-span-6.p-"[min(0.5rem,1vw)]".placeholder-sky-500.outline-none."focus:outline-sky-500"."focus:outline-offset-0"."focus:outline"."focus:outline-[min(0.125rem,0.25vw)]".caret-sky-500.rounded-"[min(0.357rem,0.714vw)]".text-center.w-full.bg-zinc-800;
+                textarea name="teams" rows="8" placeholder="One team per line, top seed first" .col-span-6.p-"[min(0.5rem,1vw)]".placeholder-sky-500.outline-none."focus:outline-sky-500"."focus:outline-offset-0"."focus:outline"."focus:outline-[min(0.125rem,0.25vw)]".caret-sky-500.rounded-"[min(0.357rem,0.714vw)]".text-center.w-full.bg-zinc-800 {}
+                select name="format" .col-span-2.p-"[min(0.5rem,1vw)]".text-sky-500.outline-none."focus:outline-sky-500"."focus:outline-offset-0"."focus:outline"."focus:outline-[min(0.125rem,0.25vw)]".rounded-"[min(0.357rem,0.714vw)]".text-center.w-full.bg-zinc-800 {
+                    option value="DOUBLE_ELIMINATION" {(format_label(db::TournamentFormat::DoubleElimination))}
+                    option value="SWISS" {(format_label(db::TournamentFormat::Swiss))}
+                }
+                select name="rules" .p-"[min(0.5rem,1vw)]".text-sky-500.outline-none."focus:outline-sky-500"."focus:outline-offset-0"."focus:outline"."focus:outline-[min(0.125rem,0.25vw)]".rounded-"[min(0.357rem,0.714vw)]".text-center.w-full.bg-zinc-800 {
+                    @for (name, _) in PRESETS {
+                        option value=(name) {(name)}
+                    }
+                }
+                select name="competition" .col-span-2.p-"[min(0.5rem,1vw)]".text-sky-500.outline-none."focus:outline-sky-500"."focus:outline-offset-0"."focus:outline"."focus:outline-[min(0.125rem,0.25vw)]".rounded-"[min(0.357rem,0.714vw)]".text-center.w-full.bg-zinc-800 {
+                    option value="" {"No competition"}
+                    @for competition in competitions {
+                        option value=(competition.id) {(competition.name)}
+                    }
+                }
+                input type="submit" value="Create" .w-full.bg-sky-500.p-"[min(0.5rem,1vw)]".cursor-pointer.rounded-"[min(0.357rem,0.714vw)]"."hover:bg-sky-400"."active:bg-sky-300"."focus:outline-none"."focus:bg-sky-400";
+            }
+            a href="/" hx-boost="true" .block.bg-sky-500.p-"[min(0.5rem,1vw)]".rounded-"[min(0.357rem,0.714vw)]"."hover:bg-sky-400"."active:bg-sky-300" {"Back"}
+        }
+    }
+}
+
+fn tournament_entry(tournament: &db::TournamentInfo) -> Markup {
+    html! {
+        a href={"/tournament/" (tournament.id)} hx-boost="true" .flex.gap-"[min(0.5rem,1vw)]".bg-zinc-700.p-"[min(0.5rem,1vw)]".rounded-"[min(0.357rem,0.714vw)]"."hover:bg-zinc-600"."active:bg-zinc-500" {
+            div .w-full.flex-initial.truncate {(tournament.name)}
+            div .w-full.flex-initial.text-zinc-400 {(format_label(tournament.format))}
+        }
+    }
+}
+
+pub fn add_tournament_entry(tournament: &db::TournamentInfo) -> Markup {
+    html! {
+        div #tournament_list hx-swap-oob="beforeend" {
+            (tournament_entry(tournament))
+        }
+    }
+}
+
+pub fn tournament_page(tournament: &db::Tournament) -> Markup {
+    html! {
+        div #error {}
+        div .max-w-6xl.mx-auto.p-"[min(0.5rem,1vw)]".text-"[min(1rem,2vw)]".text-center.flex.flex-col.gap-"[min(0.5rem,1vw)]" {
+            div .bg-sky-500.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]".truncate {
+                (tournament.name) " - " (format_label(tournament.format))
+            }
+            div ws-connect={"/ws/tournament/" (tournament.id)} {
+                div #tournament {}
+            }
+            form hx-post={"/next_round/" (tournament.id)} hx-swap="none" .grid.grid-cols-6.bg-zinc-700.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]".gap-"[min(0.5rem,1vw)]" {
+                input type="text" name="date" placeholder="Round date" .col-span-5.p-"[min(0.5rem,1vw)]".placeholder-sky-500.outline-none."focus:outline-sky-500"."focus:outline-offset-0"."focus:outline"."focus:outline-[min(0.125rem,0.25vw)]".caret-sky-500.rounded-"[min(0.357rem,0.714vw)]".text-center.w-full.bg-zinc-800;
+                input type="submit" value="Next round" .w-full.bg-sky-500.p-"[min(0.5rem,1vw)]".cursor-pointer.rounded-"[min(0.357rem,0.714vw)]"."hover:bg-sky-400"."active:bg-sky-300"."focus:outline-none"."focus:bg-sky-400";
+            }
+            a href="/tournaments" hx-boost="true" .block.bg-sky-500.p-"[min(0.5rem,1vw)]".rounded-"[min(0.357rem,0.714vw)]"."hover:bg-sky-400"."active:bg-sky-300" {"Back"}
+        }
+    }
+}
+
+pub fn tournament_view(tournament: &db::Tournament, records: &[schedule::Record]) -> Markup {
+    let eliminated = |record: &schedule::Record| {
+        tournament.format == db::TournamentFormat::DoubleElimination && record.losses >= 2
+    };
+    html! {
+        div #tournament .flex.flex-col.gap-"[min(0.5rem,1vw)]" {
+            div .bg-sky-500.flex.items-center.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]".gap-"[min(0.5rem,1vw)]" {
+                div .w-full.flex-initial {"#"}
+                div .w-full.flex-initial {"Team"}
+                div .w-full.flex-initial {"Seed"}
+                div .w-full.flex-initial {"Won"}
+                div .w-full.flex-initial {"Lost"}
+                div .w-full.flex-initial {"Byes"}
+            }
+            @for (position, record) in records.iter().enumerate() {
+                div .bg-zinc-700.flex.items-center.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]".gap-"[min(0.5rem,1vw)]" .text-zinc-400.line-through[eliminated(record)] {
+                    div .w-full.flex-initial {(position + 1)}
+                    div .w-full.flex-initial.truncate {(record.team)}
+                    div .w-full.flex-initial {(record.seed)}
+                    div .w-full.flex-initial {(record.wins)}
+                    div .w-full.flex-initial {(record.losses)}
+                    div .w-full.flex-initial {(record.byes)}
+                }
+            }
+            @for round in (0..tournament.rounds()).rev() {
+                div .bg-zinc-700.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]".flex.flex-col.gap-"[min(0.25rem,0.5vw)]" {
+                    div .text-sky-500 {"Round " (round + 1)}
+                    @for pairing in tournament.pairings.iter().filter(|pairing| pairing.round == round) {
+                        @match &pairing.match_info {
+                            Some(match_info) => {
+                                a href={"/match/" (match_info.id)} hx-boost="true" .grid.grid-cols-3.rounded-"[min(0.357rem,0.714vw)]"."hover:bg-zinc-600" {
+                                    div .truncate {(match_info.team_a)}
+                                    div {
+                                        @if match_info.status == db::MatchStatus::Planned {
+                                            "vs"
+                                        } @else {
+                                            (match_info.result[0]) ":" (match_info.result[1])
+                                        }
+                                    }
+                                    div .truncate {(match_info.team_b)}
+                                }
+                            },
+                            None => {
+                                div .grid.grid-cols-3 {
+                                    div .truncate {(tournament.team_name(pairing.team_a_id))}
+                                    div .text-zinc-400 {"Bye"}
+                                    div {}
+                                }
+                            },
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+const TIE_BREAKER_VALUES: [(&str, db::TieBreaker); 4] = [
+    ("MATCH_POINTS", db::TieBreaker::MatchPoints),
+    ("SETS_RATIO", db::TieBreaker::SetsRatio),
+    ("POINTS_RATIO", db::TieBreaker::PointsRatio),
+    ("HEAD_TO_HEAD", db::TieBreaker::HeadToHead),
+];
+
+fn tie_breaker_label(tie_breaker: db::TieBreaker) -> &'static str {
+    match tie_breaker {
+        db::TieBreaker::MatchPoints => "Match points",
+        db::TieBreaker::SetsRatio => "Sets ratio",
+        db::TieBreaker::PointsRatio => "Points ratio",
+        db::TieBreaker::HeadToHead => "Head-to-head",
+    }
+}
+
+pub fn pool_plays_page(pool_plays: &[db::PoolPlayInfo], competitions: &[db::Competition]) -> Markup {
+    html! {
+        div #error {}
+        div .max-w-6xl.mx-auto.p-"[min(0.5rem,1vw)]".text-"[min(1rem,2vw)]".text-center.flex.flex-col.gap-"[min(0.5rem,1vw)]" {
+            div .bg-sky-500.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]" {"Pools and playoffs"}
+            div #pool_play_list .flex.flex-col.gap-"[min(0.5rem,1vw)]" {
+                @for pool_play in pool_plays {
+                    (pool_play_entry(pool_play))
+                }
+            }
+            form hx-post="/add_pool_play" hx-swap="none" .grid.grid-cols-6.bg-zinc-700.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]".gap-"[min(0.5rem,1vw)]" {
+                input type="text" name="name" placeholder="Tournament name" .col-span-6.p-"[min(0.5rem,1vw)]".placeholder-sky-500.outline-none."focus:outline-sky-500"."focus:outline-offset-0"."focus:outline"."focus:outline-[min(0.125rem,0.25vw)]".caret-sky-500.rounded-"[min(0.357rem,0.714vw)]".text-center.w-full.bg-zinc-800;
+                textarea name="pools" rows="6" placeholder="One pool per line, teams separated with commas" .col-span-6.p-"[min(0.5rem,1vw)]".placeholder-sky-500.outline-none."focus:outline-sky-500"."focus:outline-offset-0"."focus:outline"."focus:outline-[min(0.125rem,0.25vw)]".caret-sky-500.rounded-"[min(0.357rem,0.714vw)]".text-center.w-full.bg-zinc-800 {}
+                input type="text" name="start" placeholder="First round date" .col-span-2.p-"[min(0.5rem,1vw)]".placeholder-sky-500.outline-none."focus:outline-sky-500"."focus:outline-offset-0"."focus:outline"."focus:outline-[min(0.125rem,0.25vw)]".caret-sky-500.rounded-"[min(0.357rem,0.714vw)]".text-center.w-full.bg-zinc-800;
+                input type="text" name="round_interval" placeholder="Minutes between rounds" .col-span-2.p-"[min(0.5rem,1vw)]".placeholder-sky-500.outline-none."focus:outline-sky-500"."focus:outline-offset-0"."focus:outline"."focus:outline-[min(0.125rem,0.25vw)]".caret-sky-500.rounded-"[min(0.357rem,0.714vw)]".text-center.w-full.bg-zinc-800;
+                input type="text" name="advancing" placeholder="Teams advancing per pool" .col-span-2.p-"[min(0.5rem,1vw)]".placeholder-sky-500.outline-none."focus:outline-sky-500"."focus:outline-offset-0"."focus:outline"."focus:outline-[min(0.125rem,0.25vw)]".caret-sky-500.rounded-"[min(0.357rem,0.714vw)]".text-center.w-full.bg-zinc-800;
+                div .col-span-6.grid.grid-cols-4.gap-"[min(0.5rem,1vw)]" {
+                    @for (index, default) in db::TieBreaker::ALL.into_iter().enumerate() {
+                        select name={"tie_breaker_" (index + 1)} title={"Tie-breaker " (index + 1)} .p-"[min(0.5rem,1vw)]".text-sky-500.outline-none."focus:outline-sky-500"."focus:outline-offset-0"."focus:outline"."focus:outline-[min(0.125rem,0.25vw)]".rounded-"[min(0.357rem,0.714vw)]".text-center.w-full.bg-zinc-800 {
+                            @for (value, tie_breaker) in TIE_BREAKER_VALUES {
+                                option value=(value) selected[tie_breaker == default] {(index + 1) ". " (tie_breaker_label(tie_breaker))}
+                            }
+                        }
+                    }
+                }
+                select name="rules" .col-span-2.p-"[min(0.5rem,1vw)]".text-sky-500.outline-none."focus:outline-sky-500"."focus:outline-offset-0"."focus:outline"."focus:outline-[min(0.125rem,0.25vw)]".rounded-"[min(0.357rem,0.714vw)]".text-center.w-full.bg-zinc-800 {
+                    @for (name, _) in PRESETS {
+                        option value=(name) {(name)}
+                    }
+                }
+                select name="competition" .col-span-3.p-"[min(0.5rem,1vw)]".text-sky-500.outline-none."focus:outline-sky-500"."focus:outline-offset-0"."focus:outline"."focus:outline-[min(0.125rem,0.25vw)]".rounded-"[min(0.357rem,0.714vw)]".text-center.w-full.bg-zinc-800 {
+                    option value="" {"No competition"}
+                    @for competition in competitions {
+                        option value=(competition.id) {(competition.name)}
+                    }
+                }
+                input type="submit" value="Create" .w-full.bg-sky-500.p-"[min(0.5rem,1vw)]".cursor-pointer.rounded-"[min(0.357rem,0.714vw)]"."hover:bg-sky-400"."active:bg-sky-300"."focus:outline-none"."focus:bg-sky-400";
+            }
+            a href="/" hx-boost="true" .block.bg-sky-500.p-"[min(0.5rem,1vw)]".rounded-"[min(0.357rem,0.714vw)]"."hover:bg-sky-400"."active:bg-sky-300" {"Back"}
+        }
+    }
+}
+
+fn pool_play_entry(pool_play: &db::PoolPlayInfo) -> Markup {
+    html! {
+        a href={"/pool_play/" (pool_play.id)} hx-boost="true" .block.truncate.bg-zinc-700.p-"[min(0.5rem,1vw)]".rounded-"[min(0.357rem,0.714vw)]"."hover:bg-zinc-600"."active:bg-zinc-500" {(pool_play.name)}
+    }
+}
+
+pub fn add_pool_play_entry(pool_play: &db::PoolPlayInfo) -> Markup {
+    html! {
+        div #pool_play_list hx-swap-oob="beforeend" {
+            (pool_play_entry(pool_play))
+        }
+    }
+}
+
+pub fn pool_play_page(pool_play: &db::PoolPlay) -> Markup {
+    html! {
+        div #error {}
+        div .max-w-6xl.mx-auto.p-"[min(0.5rem,1vw)]".text-"[min(1rem,2vw)]".text-center.flex.flex-col.gap-"[min(0.5rem,1vw)]" {
+            div .bg-sky-500.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]".truncate {(pool_play.name)}
+            div .text-zinc-400 {
+                "Tie-breakers: "
+                @for (index, tie_breaker) in pool_play.tie_breakers.iter().enumerate() {
+                    @if index > 0 {
+                        ", "
+                    }
+                    (tie_breaker_label(*tie_breaker))
+                }
+            }
+            div ws-connect={"/ws/pool_play/" (pool_play.id)} {
+                div #pool_play {}
+            }
+            form hx-post={"/create_playoffs/" (pool_play.id)} hx-swap="none" .grid.grid-cols-6.bg-zinc-700.rounded-"[min(0.357rem,0.714vw)]".p-"[min(0.5rem,1vw)]".gap-"[min(0.5rem,1vw)]" {
+                input type="text" name="first_round" placeholder="First playoff round date" .col-span-2.p-"[min(0.5rem,1vw)]".placeholder-sky-500.outline-none."focus:outline-sky-500"."focus:outline-offset-0"."focus:outline"."focus:outline-[min(0.125rem,0.25vw)]".caret-sky-500.rounded-"[min(0.357rem,0.714vw)]".text-center.w-full.bg-zinc-800;
+                input type="text" name="round_interval" placeholder="Days between rounds" .col-span-2.p-"[min(0.5rem,1vw)]".placeholder-sky-500.outline-none."focus:outline-sky-500"."focus:outline-offset-0"."focus:outline"."focus:outline-[min(0.125rem,0.25vw)]".caret-sky-500.rounded-"[min(0.357rem,0.714vw)]".text-center.w-full.bg-zinc-800;
+                select name="rules" .p-"[min(0.5rem,1vw)]".text-sky-500.outline-none."focus:outline-sky-500"."focus:outline-offset-0"."focus:outline"."focus:outline-[min(0.125rem,0.25vw)]".rounded-"[min(0.357rem,0.714vw)]".text-center.w-full.bg-zinc-800 {
+                    @for (name, _) in PRESETS {
+                        option value=(name) {(name)}
+                    }
+                }
+                input type="submit" value="Create playoffs" .w-full.bg-sky-500.p-"[min(0.5rem,1vw)]".cursor-pointer.rounded-"[min(0.357rem,0.714vw)]"."hover:bg-sky-400"."active:bg-sky-300"."focus:outline-none"."focus:bg-sky-400";
+            }
+            a href="/pool_plays" hx-boost="true" .block.bg-sky-500.p-"[min(0.5rem,1vw)]".rounded-"[min(0.357rem,0.714vw)]"."hover:bg-sky-400"."active:bg-sky-300" {"Back"}
+        }
+    }
+}
+
+pub fn pool_play_view(pool_play: &db::PoolPlay) -> Markup {
+    html! {
+        div #pool_play .flex.flex-col.gap-"[min(0.5rem,1vw)]" {
+            @for pool in &pool_play.pools {
+                div .text-sky-500 {(pool.name)}
+                (standings_rows(&standings::ranked(&pool.matches, &pool_play.tie_breakers)))
+            }
+            @if let Some(bracket_id) = pool_play.bracket_id {
+                a href={"/bracket/" (bracket_id)} hx-boost="true" .block.bg-zinc-700.p-"[min(0.5rem,1vw)]".rounded-"[min(0.357rem,0.714vw)]"."hover:bg-zinc-600"."active:bg-zinc-500" {"Playoffs"}
             }
         }
     }
@@ -117,19 +1442,39 @@ pub fn match_page_update(match_info: &db::Match) -> Markup {
                             (match_info.team_a)
                         }
                     }
+                    @if match_info.status == db::MatchStatus::InProgress {
+                        @if match_info.swapped {
+                            (serve_indicator(match_info, db::Team::B))
+                        } @else {
+                            (serve_indicator(match_info, db::Team::A))
+                        }
+                    }
+                    @if match_info.swapped {
+                        (sanction_cards(match_info, db::Team::B))
+                    } @else {
+                        (sanction_cards(match_info, db::Team::A))
+                    }
                 }
                 div .flex.flex-col.h-full.justify-end.items-start.gap-"[min(2vh,calc(3/5*2vw))]" {
                     @if match_info.status == db::MatchStatus::InProgress {
                         @if match_info.swapped {
-                            div hx-post={"/remove_point_b/" (match_info.id)} hx-swap="none" .w-"1/3".aspect-square.bg-zinc-700.text-"[min(4vh,calc(3/5*4vw))]".rounded-"[min(0.714vh,calc(3/5*0.714vw))]".grid.justify-center.content-center.cursor-pointer."hover:bg-zinc-600"."active:bg-zinc-500" {"-1"}
+                            div .flex.w-full.gap-"[min(2vh,calc(3/5*2vw))]" {
+                                div hx-post={"/remove_point_b/" (match_info.id)} hx-swap="none" .w-"1/3".aspect-square.bg-zinc-700.text-"[min(4vh,calc(3/5*4vw))]".rounded-"[min(0.714vh,calc(3/5*0.714vw))]".grid.justify-center.content-center.cursor-pointer."hover:bg-zinc-600"."active:bg-zinc-500" {"-1"}
+                                (rally_buttons(match_info, db::Team::B))
+                                (timeout_button(match_info, db::Team::B))
+                            }
                             div hx-post={"/add_point_b/" (match_info.id)} hx-swap="none" .w-full.aspect-square.bg-zinc-700.text-"[min(20vh,calc(3/5*20vw))]".rounded-"[min(0.714vh,calc(3/5*0.714vw))]".grid.justify-center.content-center.cursor-pointer."hover:bg-zinc-600"."active:bg-zinc-500" {(match_info.set_results_b.iter().last().unwrap())}
                         } @else {
-                            div hx-post={"/remove_point_a/" (match_info.id)} hx-swap="none" .w-"1/3".aspect-square.bg-zinc-700.text-"[min(4vh,calc(3/5*4vw))]".rounded-"[min(0.714vh,calc(3/5*0.714vw))]".grid.justify-center.content-center.cursor-pointer."hover:bg-zinc-600"."active:bg-zinc-500" {"-1"}
+                            div .flex.w-full.gap-"[min(2vh,calc(3/5*2vw))]" {
+                                div hx-post={"/remove_point_a/" (match_info.id)} hx-swap="none" .w-"1/3".aspect-square.bg-zinc-700.text-"[min(4vh,calc(3/5*4vw))]".rounded-"[min(0.714vh,calc(3/5*0.714vw))]".grid.justify-center.content-center.cursor-pointer."hover:bg-zinc-600"."active:bg-zinc-500" {"-1"}
+                                (rally_buttons(match_info, db::Team::A))
+                                (timeout_button(match_info, db::Team::A))
+                            }
                             div hx-post={"/add_point_a/" (match_info.id)} hx-swap="none" .w-full.aspect-square.bg-zinc-700.text-"[min(20vh,calc(3/5*20vw))]".rounded-"[min(0.714vh,calc(3/5*0.714vw))]".grid.justify-center.content-center.cursor-pointer."hover:bg-zinc-600"."active:bg-zinc-500" {(match_info.set_results_a.iter().last().unwrap())}
                         }
                     } @else {
                         div .w-"1/3".aspect-square.bg-zinc-900.text-"[min(4vh,calc(3/5*4vw))]".rounded-"[min(0.714vh,calc(3/5*0.714vw))]".grid.justify-center.content-center {"-1"}
-                        div .w-full.aspect-square.bg-zinc-900.text-"[min(20vh,calc(3/5*20vw))]".rounded-"[min(0.714vh,calc(3/5*0.714vw))]".grid.justify-center.content-center {"0"}
+                        div .w-full.aspect-square.bg-zinc-900.text-"[min(20vh,calc(3/5*20vw))]".rounded-"[min(0.714vh,calc(3/5*0.714vw))]".grid.justify-center.content-center {(match_info.set_results_a.iter().last().unwrap())}
                     }
                 }
             }
@@ -169,8 +1514,32 @@ pub fn match_page_update(match_info: &db::Match) -> Markup {
                         div .rounded-"[min(0.714vh,calc(3/5*0.714vw))]".overflow-hidden {
                             div .bg-sky-500 {"Match status"}
                             div .bg-zinc-700 {(format!("{:?}",match_info.status))}
+                            @if let Some(outcome) = outcome_label(match_info) {
+                                div .bg-zinc-700.text-amber-400.truncate {(outcome)}
+                            }
                         }
                         @if match_info.status == db::MatchStatus::InProgress {
+                            div .rounded-"[min(0.714vh,calc(3/5*0.714vw))]".overflow-hidden {
+                                div .bg-sky-500 {"Timeouts left"}
+                                div .bg-zinc-700 {
+                                    @if match_info.swapped {
+                                        (match_info.timeouts_left(db::Team::B)) " : " (match_info.timeouts_left(db::Team::A))
+                                    } @else {
+                                        (match_info.timeouts_left(db::Team::A)) " : " (match_info.timeouts_left(db::Team::B))
+                                    }
+                                }
+                            }
+                            div .rounded-"[min(0.714vh,calc(3/5*0.714vw))]".overflow-hidden {
+                                div .bg-sky-500 {"Substitutions"}
+                                div .bg-zinc-700 {
+                                    @if match_info.swapped {
+                                        (match_info.set_substitutions(db::Team::B)) " : " (match_info.set_substitutions(db::Team::A))
+                                    } @else {
+                                        (match_info.set_substitutions(db::Team::A)) " : " (match_info.set_substitutions(db::Team::B))
+                                    }
+                                }
+                            }
+                            (timeout_countdown(&timeout_label(match_info), match_info.running_timeout().map_or(0, db::Timeout::remaining)))
                             div .rounded-"[min(0.714vh,calc(3/5*0.714vw))]".overflow-hidden {
                                 div .bg-sky-500 {"Current time"}
                                 div .bg-zinc-700 #current_time {}
@@ -186,9 +1555,16 @@ pub fn match_page_update(match_info: &db::Match) -> Markup {
                         }
                     }
                     div .flex.flex-col.gap-"[min(2vh,calc(3/5*2vw))]" {
-                        @match button_status(&match_info) {
+                        @if match_info.status == db::MatchStatus::InProgress && match_info.side_switch_due {
+                            div hx-post={"/swap_teams/" (match_info.id)} hx-swap="none" .text-center.block.w-full.bg-amber-500.animate-pulse.p-"[min(2vh,calc(3/5*2vw))]".cursor-pointer.rounded-"[min(0.714vh,calc(3/5*0.714vw))]"."hover:bg-amber-400"."active:bg-amber-300" {"Switch sides"}
+                        }
+                        @match button_status(match_info) {
                             ButtonStatus::Start => {
-                                div hx-post={"/end_set/" (match_info.id)} hx-swap="none" .text-center.block.w-full.bg-sky-500.p-"[min(2vh,calc(3/5*2vw))]".cursor-pointer.rounded-"[min(0.714vh,calc(3/5*0.714vw))]"."hover:bg-sky-400"."active:bg-sky-300" {"Start match"}
+                                div .bg-sky-500.rounded-"[min(0.714vh,calc(3/5*0.714vw))]" {"Start match, first serve"}
+                                div .flex.gap-"[min(2vh,calc(3/5*2vw))]" {
+                                    div hx-post={"/start_match/" (match_info.id)} hx-vals=r#"{"serve":"A"}"# hx-swap="none" .truncate.w-full.bg-sky-500.p-"[min(2vh,calc(3/5*2vw))]".cursor-pointer.rounded-"[min(0.714vh,calc(3/5*0.714vw))]"."hover:bg-sky-400"."active:bg-sky-300" {(match_info.team_a)}
+                                    div hx-post={"/start_match/" (match_info.id)} hx-vals=r#"{"serve":"B"}"# hx-swap="none" .truncate.w-full.bg-sky-500.p-"[min(2vh,calc(3/5*2vw))]".cursor-pointer.rounded-"[min(0.714vh,calc(3/5*0.714vw))]"."hover:bg-sky-400"."active:bg-sky-300" {(match_info.team_b)}
+                                }
                             }
                             ButtonStatus::EndSet => {
                                 div hx-post={"/end_set/" (match_info.id)} hx-swap="none" .text-center.block.w-full.bg-sky-500.p-"[min(2vh,calc(3/5*2vw))]".cursor-pointer.rounded-"[min(0.714vh,calc(3/5*0.714vw))]"."hover:bg-sky-400"."active:bg-sky-300" {"End set"}
@@ -199,6 +1575,12 @@ pub fn match_page_update(match_info: &db::Match) -> Markup {
                             ButtonStatus::None => {
                             },
                         }
+                        @if !match_info.archived {
+                            (status_buttons(match_info))
+                        }
+                        @if match_info.can_undo() && !match_info.archived {
+                            div hx-post={"/undo/" (match_info.id)} hx-swap="none" .text-center.block.w-full.bg-zinc-700.p-"[min(2vh,calc(3/5*2vw))]".cursor-pointer.rounded-"[min(0.714vh,calc(3/5*0.714vw))]"."hover:bg-zinc-600"."active:bg-zinc-500" {"Undo"}
+                        }
                         a href="/" hx-boost="true" .text-center.block.w-full.bg-sky-500.p-"[min(2vh,calc(3/5*2vw))]".cursor-pointer.rounded-"[min(0.714vh,calc(3/5*0.714vw))]"."hover:bg-sky-400"."active:bg-sky-300"."focus:outline-none"."focus:bg-sky-400" {
                             "Back"
                         }
@@ -214,23 +1596,45 @@ pub fn match_page_update(match_info: &db::Match) -> Markup {
                             (match_info.team_b)
                         }
                     }
+                    @if match_info.status == db::MatchStatus::InProgress {
+                        @if match_info.swapped {
+                            (serve_indicator(match_info, db::Team::A))
+                        } @else {
+                            (serve_indicator(match_info, db::Team::B))
+                        }
+                    }
+                    @if match_info.swapped {
+                        (sanction_cards(match_info, db::Team::A))
+                    } @else {
+                        (sanction_cards(match_info, db::Team::B))
+                    }
                 }
                 div .flex.flex-col.h-full.justify-end.items-end.gap-"[min(2vh,calc(3/5*2vw))]" {
                     @if match_info.status == db::MatchStatus::InProgress {
                         @if match_info.swapped {
-                            div hx-post={"/remove_point_a/" (match_info.id)} hx-swap="none" .w-"1/3".aspect-square.bg-zinc-700.text-"[min(4vh,calc(3/5*4vw))]".rounded-"[min(0.714vh,calc(3/5*0.714vw))]".grid.justify-center.content-center.cursor-pointer."hover:bg-zinc-600"."active:bg-zinc-500" {"-1"}
+                            div .flex.w-full.justify-end.gap-"[min(2vh,calc(3/5*2vw))]" {
+                                (rally_buttons(match_info, db::Team::A))
+                                (timeout_button(match_info, db::Team::A))
+                                div hx-post={"/remove_point_a/" (match_info.id)} hx-swap="none" .w-"1/3".aspect-square.bg-zinc-700.text-"[min(4vh,calc(3/5*4vw))]".rounded-"[min(0.714vh,calc(3/5*0.714vw))]".grid.justify-center.content-center.cursor-pointer."hover:bg-zinc-600"."active:bg-zinc-500" {"-1"}
+                            }
                             div hx-post={"/add_point_a/" (match_info.id)} hx-swap="none" .w-full.aspect-square.bg-zinc-700.text-"[min(20vh,calc(3/5*20vw))]".rounded-"[min(0.714vh,calc(3/5*0.714vw))]".grid.justify-center.content-center.cursor-pointer."hover:bg-zinc-600"."active:bg-zinc-500" {(match_info.set_results_a.iter().last().unwrap())}
                         } @else {
-                            div hx-post={"/remove_point_b/" (match_info.id)} hx-swap="none" .w-"1/3".aspect-square.bg-zinc-700.text-"[min(4vh,calc(3/5*4vw))]".rounded-"[min(0.714vh,calc(3/5*0.714vw))]".grid.justify-center.content-center.cursor-pointer."hover:bg-zinc-600"."active:bg-zinc-500" {"-1"}
+                            div .flex.w-full.justify-end.gap-"[min(2vh,calc(3/5*2vw))]" {
+                                (rally_buttons(match_info, db::Team::B))
+                                (timeout_button(match_info, db::Team::B))
+                                div hx-post={"/remove_point_b/" (match_info.id)} hx-swap="none" .w-"1/3".aspect-square.bg-zinc-700.text-"[min(4vh,calc(3/5*4vw))]".rounded-"[min(0.714vh,calc(3/5*0.714vw))]".grid.justify-center.content-center.cursor-pointer."hover:bg-zinc-600"."active:bg-zinc-500" {"-1"}
+                            }
                             div hx-post={"/add_point_b/" (match_info.id)} hx-swap="none" .w-full.aspect-square.bg-zinc-700.text-"[min(20vh,calc(3/5*20vw))]".rounded-"[min(0.714vh,calc(3/5*0.714vw))]".grid.justify-center.content-center.cursor-pointer."hover:bg-zinc-600"."active:bg-zinc-500" {(match_info.set_results_b.iter().last().unwrap())}
                         }
                     } @else {
                         div .w-"1/3".aspect-square.bg-zinc-900.text-"[min(4vh,calc(3/5*4vw))]".rounded-"[min(0.714vh,calc(3/5*0.714vw))]".grid.justify-center.content-center {"-1"}
-                        div .w-full.aspect-square.bg-zinc-900.text-"[min(20vh,calc(3/5*20vw))]".rounded-"[min(0.714vh,calc(3/5*0.714vw))]".grid.justify-center.content-center {"0"}
+                        div .w-full.aspect-square.bg-zinc-900.text-"[min(20vh,calc(3/5*20vw))]".rounded-"[min(0.714vh,calc(3/5*0.714vw))]".grid.justify-center.content-center {(match_info.set_results_b.iter().last().unwrap())}
                     }
                 }
             }
         }
+        (players_panel(match_info))
+        (officials_panel(match_info))
     }
 }
 
@@ -277,6 +1681,46 @@ fn score_print(match_info: &db::Match) -> Markup {
         match_info.result[1],
         match_info.match_start.format(&format).unwrap()
     );
+    if let Some(outcome) = outcome_label(match_info) {
+        result += "\\n";
+        result += &outcome;
+    }
+    if !match_info.substitutions.is_empty() {
+        result += "\\nSubstitutions:";
+    }
+    for substitution in &match_info.substitutions {
+        let team_name = match substitution.team {
+            db::Team::A => &match_info.team_a,
+            db::Team::B => &match_info.team_b,
+        };
+        result += &format!(
+            "\\nS{} {}: {} -> {} ({}:{})",
+            substitution.set,
+            team_name,
+            substitution.player_out,
+            substitution.player_in,
+            substitution.score[0],
+            substitution.score[1]
+        );
+    }
+    if !match_info.sanctions.is_empty() {
+        result += "\\nSanctions:";
+    }
+    for sanction in &match_info.sanctions {
+        let team_name = match sanction.team {
+            db::Team::A => &match_info.team_a,
+            db::Team::B => &match_info.team_b,
+        };
+        result += &format!(
+            "\\nS{} {} {}: {:?} ({}:{})",
+            sanction.set,
+            team_name,
+            sanction_player(sanction),
+            sanction.sanction,
+            sanction.score[0],
+            sanction.score[1]
+        );
+    }
     PreEscaped(result)
 }
 
@@ -310,12 +1754,26 @@ fn match_entry(match_info: &db::Match) -> Markup {
             div .w-full.flex-initial.truncate {
                 (match_info.team_b)
             }
-            div .w-full.flex-initial.min-w-max {
-                (match_info.match_start.format(&format).unwrap())
+            @if matches!(match_info.status, db::MatchStatus::Postponed | db::MatchStatus::Cancelled) {
+                div .w-full.flex-initial.min-w-max.line-through.text-zinc-400 {
+                    (match_info.match_start.format(&format).unwrap())
+                }
+            } @else {
+                div .w-full.flex-initial.min-w-max {
+                    (match_info.match_start.format(&format).unwrap())
+                    @if let Some(court) = &match_info.court {
+                        div .truncate.text-zinc-400 {(court)}
+                    }
+                }
             }
             @if match_info.status == db::MatchStatus::Finished {
                 div onclick={"handle_clipboard(event,'" (score_print(match_info)) "')"} .ignore.cursor-pointer.w-full.flex-initial.self-stretch.flex.justify-center.items-center.gap-"[min(0.25rem,0.5vw)]" {
                     (format!("{}:{}",match_info.result[0],match_info.result[1]))
+                    @match match_info.outcome {
+                        Some(db::Outcome::Forfeit(_)) => div .text-amber-400 {"(forfeit)"},
+                        Some(db::Outcome::Retirement(_)) => div .text-amber-400 {"(retired)"},
+                        None => {},
+                    }
                     div .clipboard.h-"[min(1rem,2vw)]".self-center {
                         svg viewBox="0 0 24 24" .size-full {
                             use xlink:href="#clipboard-icon";
@@ -332,8 +1790,10 @@ fn match_entry(match_info: &db::Match) -> Markup {
                 (format!("{:?}",match_info.status))
             }
             div .w-full.flex-initial {
-                div hx-trigger="click consume" hx-post={"/remove_match/" (match_info.id)} hx-swap="none" .ignore.bg-sky-500.p-"[min(0.5rem,1vw)]".my-"[min(0.5rem,1vw)]".cursor-pointer.rounded-"[min(0.357rem,0.714vw)]"."hover:bg-sky-400"."active:bg-sky-300" {
-                  "Delete"
+                @if !match_info.archived {
+                    div hx-trigger="click consume" hx-post={"/remove_match/" (match_info.id)} hx-swap="none" .ignore.bg-sky-500.p-"[min(0.5rem,1vw)]".my-"[min(0.5rem,1vw)]".cursor-pointer.rounded-"[min(0.357rem,0.714vw)]"."hover:bg-sky-400"."active:bg-sky-300" {
+                      "Delete"
+                    }
                 }
             }
         }
@@ -350,6 +1810,134 @@ pub fn match_list(matches: &[db::Match]) -> Markup {
     }
 }
 
+fn status_buttons(match_info: &db::Match) -> Markup {
+    let status_button = |status: &str, label: &str| {
+        html! {
+            div hx-post={"/change_status/" (match_info.id)} hx-vals={r#"{"status":""# (status) r#""}"#} hx-swap="none" .truncate.text-center.block.w-full.bg-zinc-700.p-"[min(2vh,calc(3/5*2vw))]".cursor-pointer.rounded-"[min(0.714vh,calc(3/5*0.714vw))]"."hover:bg-zinc-600"."active:bg-zinc-500" {(label)}
+        }
+    };
+    html! {
+        @if match_info.status == db::MatchStatus::Postponed {
+            form hx-post={"/reschedule/" (match_info.id)} hx-swap="none" .flex.gap-"[min(2vh,calc(3/5*2vw))]" {
+                input type="text" name="date" placeholder="New date" .w-full.min-w-0.p-"[min(2vh,calc(3/5*2vw))]".placeholder-sky-500.outline-none."focus:outline-sky-500"."focus:outline-offset-0"."focus:outline"."focus:outline-[min(0.125rem,0.25vw)]".caret-sky-500.rounded-"[min(0.714vh,calc(3/5*0.714vw))]".text-center.bg-zinc-700;
+                input type="submit" value="Reschedule" .w-full.bg-sky-500.p-"[min(2vh,calc(3/5*2vw))]".cursor-pointer.rounded-"[min(0.714vh,calc(3/5*0.714vw))]"."hover:bg-sky-400"."active:bg-sky-300"."focus:outline-none"."focus:bg-sky-400";
+            }
+        }
+        @if match_info.status == db::MatchStatus::Suspended {
+            div hx-post={"/change_status/" (match_info.id)} hx-vals=r#"{"status":"IN_PROGRESS"}"# hx-swap="none" .text-center.block.w-full.bg-sky-500.p-"[min(2vh,calc(3/5*2vw))]".cursor-pointer.rounded-"[min(0.714vh,calc(3/5*0.714vw))]"."hover:bg-sky-400"."active:bg-sky-300" {"Resume"}
+        }
+        div .flex.gap-"[min(2vh,calc(3/5*2vw))]" {
+            @if match_info.status.can_change_to(db::MatchStatus::Postponed) {
+                (status_button("POSTPONED", "Postpone"))
+            }
+            @if match_info.status.can_change_to(db::MatchStatus::Suspended) {
+                (status_button("SUSPENDED", "Suspend"))
+            }
+            @if match_info.status.can_change_to(db::MatchStatus::Cancelled) {
+                (status_button("CANCELLED", "Cancel"))
+            }
+        }
+    }
+}
+
+fn rally_type_label(rally_type: db::RallyType) -> &'static str {
+    match rally_type {
+        db::RallyType::Ace => "Serve ace",
+        db::RallyType::Attack => "Attack kill",
+        db::RallyType::Block => "Block",
+        db::RallyType::OpponentError => "Opponent error",
+    }
+}
+
+fn rally_buttons(match_info: &db::Match, team: db::Team) -> Markup {
+    let path = match team {
+        db::Team::A => "/add_point_a/",
+        db::Team::B => "/add_point_b/",
+    };
+    let buttons = [
+        (db::RallyType::Ace, "ACE", "ACE"),
+        (db::RallyType::Attack, "ATTACK", "ATK"),
+        (db::RallyType::Block, "BLOCK", "BLK"),
+        (db::RallyType::OpponentError, "OPPONENT_ERROR", "ERR"),
+    ];
+    html! {
+        div .w-"1/3".aspect-square.grid.grid-cols-2.gap-"[min(1vh,calc(3/5*1vw))]".text-"[min(2vh,calc(3/5*2vw))]" {
+            @for (rally_type, value, short) in buttons {
+                div hx-post={(path) (match_info.id)} hx-vals={r#"{"rally_type":""# (value) r#""}"#} hx-swap="none" title=(rally_type_label(rally_type)) .bg-zinc-700.rounded-"[min(0.714vh,calc(3/5*0.714vw))]".grid.justify-center.content-center.cursor-pointer."hover:bg-zinc-600"."active:bg-zinc-500" {(short)}
+            }
+        }
+    }
+}
+
+fn timeout_button(match_info: &db::Match, team: db::Team) -> Markup {
+    let path = match team {
+        db::Team::A => "/timeout_a/",
+        db::Team::B => "/timeout_b/",
+    };
+    html! {
+        @if match_info.timeouts_left(team) > 0 && match_info.running_timeout().is_none() {
+            div hx-post={(path) (match_info.id)} hx-swap="none" .w-"1/3".aspect-square.bg-zinc-700.text-"[min(4vh,calc(3/5*4vw))]".rounded-"[min(0.714vh,calc(3/5*0.714vw))]".grid.justify-center.content-center.cursor-pointer."hover:bg-zinc-600"."active:bg-zinc-500" {"T/O"}
+        } @else {
+            div .w-"1/3".aspect-square.bg-zinc-900.text-"[min(4vh,calc(3/5*4vw))]".rounded-"[min(0.714vh,calc(3/5*0.714vw))]".grid.justify-center.content-center {"T/O"}
+        }
+    }
+}
+
+pub fn timeout_label(match_info: &db::Match) -> String {
+    match match_info.timeout.as_ref().map(|timeout| timeout.team) {
+        Some(Some(db::Team::A)) => format!("Timeout: {}", match_info.team_a),
+        Some(Some(db::Team::B)) => format!("Timeout: {}", match_info.team_b),
+        Some(None) => "Technical timeout".to_owned(),
+        None => String::new(),
+    }
+}
+
+pub fn timeout_countdown(label: &str, remaining: i32) -> Markup {
+    html! {
+        div #timeout_countdown .rounded-"[min(0.714vh,calc(3/5*0.714vw))]".overflow-hidden {
+            @if remaining > 0 {
+                div .bg-amber-500.truncate {(label)}
+                div .bg-zinc-700 {(remaining) "s"}
+            }
+        }
+    }
+}
+
+fn sanction_cards(match_info: &db::Match, team: db::Team) -> Markup {
+    html! {
+        div .flex.justify-center.gap-"[min(1vh,calc(3/5*1vw))]".text-"[min(2vh,calc(3/5*2vw))]".text-zinc-900 {
+            @for sanction in match_info.sanctions.iter().filter(|sanction| sanction.team == team) {
+                @match sanction.sanction {
+                    db::Sanction::Warning => div .bg-yellow-400.px-"[min(0.5vh,calc(3/5*0.5vw))]".rounded-sm title="Warning" {(sanction_player(sanction))},
+                    db::Sanction::Penalty => div .bg-red-500.px-"[min(0.5vh,calc(3/5*0.5vw))]".rounded-sm title="Penalty" {(sanction_player(sanction))},
+                    db::Sanction::Expulsion => div .bg-red-500.text-white.px-"[min(0.5vh,calc(3/5*0.5vw))]".rounded-sm title="Expulsion" {"EXP " (sanction_player(sanction))},
+                    db::Sanction::Disqualification => div .bg-zinc-100.px-"[min(0.5vh,calc(3/5*0.5vw))]".rounded-sm title="Disqualification" {"DSQ " (sanction_player(sanction))},
+                }
+            }
+        }
+    }
+}
+
+fn sanction_player(sanction: &db::SanctionRecord) -> String {
+    match sanction.player {
+        Some(player) => format!("#{player}"),
+        None => "Team".to_owned(),
+    }
+}
+
+fn serve_indicator(match_info: &db::Match, team: db::Team) -> Markup {
+    html! {
+        div .text-"[min(2.5vh,calc(3/5*2.5vw))]".flex.justify-center.items-center.gap-"[min(1vh,calc(3/5*1vw))]" {
+            @if match_info.serving == Some(team) {
+                div .size-"[min(2vh,calc(3/5*2vw))]".rounded-full.bg-amber-400 {}
+                "Serving: P" (match_info.server_position(team))
+            } @else if match_info.serving.is_some() {
+                div .text-zinc-400 {"Next server: P" (match_info.server_position(team))}
+            }
+        }
+    }
+}
+
 fn swap_icon() -> Markup {
     html! {
         svg fill="none" viewBox="0 0 24 24" stroke-width="1.5" stroke="currentColor" class="size-full" {
@@ -378,27 +1966,19 @@ enum ButtonStatus {
 }
 
 fn button_status(match_info: &db::Match) -> ButtonStatus {
-    if match_info.status == db::MatchStatus::Finished {
-        return ButtonStatus::None;
-    }
-    if match_info.status == db::MatchStatus::Planned {
-        return ButtonStatus::Start;
+    match match_info.status {
+        db::MatchStatus::Planned => return ButtonStatus::Start,
+        db::MatchStatus::InProgress => {}
+        _ => return ButtonStatus::None,
     }
     let set_points_a = *match_info.set_results_a.last().unwrap();
     let set_points_b = *match_info.set_results_b.last().unwrap();
-    if match_info.result[0] == 2 && match_info.result[1] == 2 {
-        if (set_points_a < 15 && set_points_b < 15) || set_points_a.abs_diff(set_points_b) < 2 {
-            return ButtonStatus::None;
-        }
-    } else {
-        if (set_points_a < 25 && set_points_b < 25) || set_points_a.abs_diff(set_points_b) < 2 {
-            return ButtonStatus::None;
-        }
-    }
-    let result_index = if set_points_a > set_points_b { 0 } else { 1 };
-    if match_info.result[result_index] >= 2 {
-        ButtonStatus::EndMatch
-    } else {
-        ButtonStatus::EndSet
+    match match_info
+        .rules
+        .set_outcome(&match_info.result, set_points_a, set_points_b)
+    {
+        SetOutcome::InProgress => ButtonStatus::None,
+        SetOutcome::SetWon(_) => ButtonStatus::EndSet,
+        SetOutcome::MatchWon(_) => ButtonStatus::EndMatch,
     }
 }
@@ -0,0 +1,196 @@
+use sqlx::{
+    error::BoxDynError,
+    postgres::{types::PgRecordDecoder, PgTypeInfo, PgValueRef},
+    Decode, Encode, Postgres, Type,
+};
+
+#[derive(Encode, Clone, Copy, PartialEq, Eq, Debug)]
+#[sqlx(type_name = "scoring_rules")]
+pub struct ScoringRules {
+    pub sets_to_win: i32,
+    pub set_target: i32,
+    pub deciding_set_target: i32,
+    pub min_lead: i32,
+    pub point_cap: Option<i32>,
+    pub switch_interval: Option<i32>,
+    pub deciding_switch_interval: Option<i32>,
+    pub timeouts_per_set: i32,
+    pub timeout_seconds: i32,
+    pub technical_timeout_at: Option<i32>,
+    pub substitutions_per_set: i32,
+}
+
+impl Type<Postgres> for ScoringRules {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::with_name("scoring_rules")
+    }
+}
+
+// Derived composite decoding does not support Option fields in sqlx 0.7
+impl<'r> Decode<'r, Postgres> for ScoringRules {
+    fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        let mut decoder = PgRecordDecoder::new(value)?;
+        Ok(ScoringRules {
+            sets_to_win: decoder.try_decode()?,
+            set_target: decoder.try_decode()?,
+            deciding_set_target: decoder.try_decode()?,
+            min_lead: decoder.try_decode()?,
+            point_cap: decoder.try_decode()?,
+            switch_interval: decoder.try_decode()?,
+            deciding_switch_interval: decoder.try_decode()?,
+            timeouts_per_set: decoder.try_decode()?,
+            timeout_seconds: decoder.try_decode()?,
+            technical_timeout_at: decoder.try_decode()?,
+            substitutions_per_set: decoder.try_decode()?,
+        })
+    }
+}
+
+pub const PRESETS: [(&str, ScoringRules); 4] = [
+    (
+        "Indoor",
+        ScoringRules {
+            sets_to_win: 3,
+            set_target: 25,
+            deciding_set_target: 15,
+            min_lead: 2,
+            point_cap: None,
+            switch_interval: None,
+            deciding_switch_interval: None,
+            timeouts_per_set: 2,
+            timeout_seconds: 30,
+            technical_timeout_at: None,
+            substitutions_per_set: 6,
+        },
+    ),
+    (
+        "Youth",
+        ScoringRules {
+            sets_to_win: 2,
+            set_target: 25,
+            deciding_set_target: 15,
+            min_lead: 2,
+            point_cap: None,
+            switch_interval: None,
+            deciding_switch_interval: None,
+            timeouts_per_set: 2,
+            timeout_seconds: 30,
+            technical_timeout_at: None,
+            substitutions_per_set: 6,
+        },
+    ),
+    (
+        "Recreational",
+        ScoringRules {
+            sets_to_win: 2,
+            set_target: 25,
+            deciding_set_target: 15,
+            min_lead: 2,
+            point_cap: Some(27),
+            switch_interval: None,
+            deciding_switch_interval: None,
+            timeouts_per_set: 2,
+            timeout_seconds: 30,
+            technical_timeout_at: None,
+            substitutions_per_set: 6,
+        },
+    ),
+    (
+        "Beach",
+        ScoringRules {
+            sets_to_win: 2,
+            set_target: 21,
+            deciding_set_target: 15,
+            min_lead: 2,
+            point_cap: None,
+            switch_interval: Some(7),
+            deciding_switch_interval: Some(5),
+            timeouts_per_set: 1,
+            timeout_seconds: 30,
+            technical_timeout_at: Some(21),
+            substitutions_per_set: 0,
+        },
+    ),
+];
+
+/// Typical length of a set, used to estimate match length
+pub const SET_MINUTES: i32 = 30;
+
+#[derive(PartialEq, Eq, Debug)]
+pub enum SetOutcome {
+    InProgress,
+    SetWon(usize),
+    MatchWon(usize),
+}
+
+impl ScoringRules {
+    pub fn preset(name: &str) -> Option<ScoringRules> {
+        PRESETS
+            .iter()
+            .find(|(preset_name, _)| preset_name.eq_ignore_ascii_case(name.trim()))
+            .map(|(_, rules)| *rules)
+    }
+
+    /// Time a match is expected to take where no venue slot says otherwise
+    pub fn expected_minutes(&self) -> i32 {
+        self.sets_to_win * SET_MINUTES
+    }
+
+    /// Single set to the deciding set target, played when a two-legged tie is level
+    pub fn golden_set(&self) -> ScoringRules {
+        ScoringRules {
+            sets_to_win: 1,
+            set_target: self.deciding_set_target,
+            switch_interval: self.deciding_switch_interval,
+            technical_timeout_at: None,
+            ..*self
+        }
+    }
+
+    pub fn is_deciding_set(&self, result: &[i32]) -> bool {
+        result[0] == self.sets_to_win - 1 && result[1] == self.sets_to_win - 1
+    }
+
+    pub fn target(&self, result: &[i32]) -> i32 {
+        if self.is_deciding_set(result) {
+            self.deciding_set_target
+        } else {
+            self.set_target
+        }
+    }
+
+    pub fn side_switch_due(&self, result: &[i32], set_points_a: i32, set_points_b: i32) -> bool {
+        let interval = if self.is_deciding_set(result) {
+            self.deciding_switch_interval
+        } else {
+            self.switch_interval
+        };
+        let total = set_points_a + set_points_b;
+        interval.is_some_and(|interval| total > 0 && total % interval == 0)
+    }
+
+    pub fn technical_timeout_due(
+        &self,
+        result: &[i32],
+        set_points_a: i32,
+        set_points_b: i32,
+    ) -> bool {
+        !self.is_deciding_set(result)
+            && self.technical_timeout_at == Some(set_points_a + set_points_b)
+    }
+
+    pub fn set_outcome(&self, result: &[i32], set_points_a: i32, set_points_b: i32) -> SetOutcome {
+        let leading = set_points_a.max(set_points_b);
+        let lead = set_points_a.abs_diff(set_points_b) as i32;
+        let capped = self.point_cap.is_some_and(|cap| leading >= cap) && lead > 0;
+        if !capped && (leading < self.target(result) || lead < self.min_lead) {
+            return SetOutcome::InProgress;
+        }
+        let result_index = if set_points_a > set_points_b { 0 } else { 1 };
+        if result[result_index] + 1 >= self.sets_to_win {
+            SetOutcome::MatchWon(result_index)
+        } else {
+            SetOutcome::SetWon(result_index)
+        }
+    }
+}
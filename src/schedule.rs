@@ -0,0 +1,732 @@
+use std::{
+    collections::{HashMap, HashSet},
+    error::Error,
+    fmt::Display,
+};
+
+use sqlx::PgConnection;
+use time::{format_description, Duration, PrimitiveDateTime, Weekday};
+
+use crate::{db, standings};
+
+pub struct RoundRobin<'a> {
+    pub teams: Vec<&'a str>,
+    pub start: &'a str,
+    pub match_days: Vec<Weekday>,
+    pub double_round: bool,
+    pub ruleset: &'a str,
+    pub competition: &'a str,
+}
+
+#[derive(Debug)]
+pub enum ScheduleError {
+    NotEnoughTeams,
+    DuplicateTeam,
+    NoMatchDays,
+    Match(db::MatchAddError),
+}
+
+impl Display for ScheduleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScheduleError::NotEnoughTeams => f.write_str("At least two teams are needed"),
+            ScheduleError::DuplicateTeam => f.write_str("Every team can be listed only once"),
+            ScheduleError::NoMatchDays => f.write_str("Select at least one match day"),
+            ScheduleError::Match(e) => e.fmt(f),
+        }
+    }
+}
+
+impl Error for ScheduleError {}
+
+/// Pairings of every round using the circle method, a bye is added for an odd number of teams
+pub fn round_robin(team_count: usize, double_round: bool) -> Vec<Vec<(usize, usize)>> {
+    let mut slots: Vec<Option<usize>> = (0..team_count).map(Some).collect();
+    if team_count % 2 == 1 {
+        slots.push(None);
+    }
+    let slot_count = slots.len();
+    let mut rounds = Vec::new();
+    for round in 0..slot_count - 1 {
+        let mut pairings = Vec::new();
+        for i in 0..slot_count / 2 {
+            if let (Some(home), Some(away)) = (slots[i], slots[slot_count - 1 - i]) {
+                // The fixed team alternates between home and away
+                if i == 0 && round % 2 == 1 {
+                    pairings.push((away, home));
+                } else {
+                    pairings.push((home, away));
+                }
+            }
+        }
+        rounds.push(pairings);
+        slots[1..].rotate_right(1);
+    }
+    if double_round {
+        let return_rounds: Vec<Vec<(usize, usize)>> = rounds
+            .iter()
+            .map(|pairings| pairings.iter().map(|&(home, away)| (away, home)).collect())
+            .collect();
+        rounds.extend(return_rounds);
+    }
+    rounds
+}
+
+/// First `rounds` dates from `start` falling on one of the match days
+pub fn round_dates(
+    start: PrimitiveDateTime,
+    match_days: &[Weekday],
+    rounds: usize,
+) -> Vec<PrimitiveDateTime> {
+    let mut dates = Vec::new();
+    let mut date = start;
+    while dates.len() < rounds {
+        if match_days.contains(&date.weekday()) {
+            dates.push(date);
+        }
+        date += Duration::days(1);
+    }
+    dates
+}
+
+pub async fn generate(
+    conn: &mut PgConnection,
+    schedule: &RoundRobin<'_>,
+) -> Result<Vec<Vec<db::Match>>, ScheduleError> {
+    if schedule.teams.len() < 2 {
+        return Err(ScheduleError::NotEnoughTeams);
+    }
+    let keys: HashSet<String> = schedule.teams.iter().map(|team| db::team_key(team)).collect();
+    if keys.len() != schedule.teams.len() {
+        return Err(ScheduleError::DuplicateTeam);
+    }
+    if schedule.match_days.is_empty() {
+        return Err(ScheduleError::NoMatchDays);
+    }
+    let start = db::parse_future_date(schedule.start).map_err(ScheduleError::Match)?;
+    let pairings = round_robin(schedule.teams.len(), schedule.double_round);
+    let dates = round_dates(start, &schedule.match_days, pairings.len());
+    db::lock_teams(conn, &schedule.teams).await;
+    let format = format_description::parse("[year]-[month]-[day]T[hour]:[minute]").unwrap();
+    let mut rounds = Vec::new();
+    for (round, date) in pairings.into_iter().zip(dates) {
+        let date = date.format(&format).unwrap();
+        let mut matches = Vec::new();
+        for (home, away) in round {
+            let match_info = db::add_match(
+                conn,
+                schedule.teams[home],
+                schedule.teams[away],
+                &date,
+                schedule.ruleset,
+                schedule.competition,
+                "",
+            )
+            .await
+            .map_err(ScheduleError::Match)?;
+            matches.push(match_info);
+        }
+        rounds.push(matches);
+    }
+    Ok(rounds)
+}
+
+pub struct Record {
+    pub team_id: i32,
+    pub team: String,
+    pub seed: i32,
+    pub wins: i32,
+    pub losses: i32,
+    pub byes: i32,
+    opponents: HashSet<i32>,
+}
+
+/// Records of all teams ranked the way the format pairs them, a Swiss bye counts as a win
+pub fn records(tournament: &db::Tournament) -> Vec<Record> {
+    let mut records: Vec<Record> = tournament
+        .teams
+        .iter()
+        .map(|team| Record {
+            team_id: team.id,
+            team: team.name.clone(),
+            seed: team.seed,
+            wins: 0,
+            losses: 0,
+            byes: 0,
+            opponents: HashSet::new(),
+        })
+        .collect();
+    let index: HashMap<i32, usize> = records
+        .iter()
+        .enumerate()
+        .map(|(index, record)| (record.team_id, index))
+        .collect();
+    for pairing in &tournament.pairings {
+        let a = index[&pairing.team_a_id];
+        let Some(team_b_id) = pairing.team_b_id else {
+            records[a].byes += 1;
+            if tournament.format == db::TournamentFormat::Swiss {
+                records[a].wins += 1;
+            }
+            continue;
+        };
+        let b = index[&team_b_id];
+        records[a].opponents.insert(team_b_id);
+        records[b].opponents.insert(pairing.team_a_id);
+        let Some(match_info) = &pairing.match_info else {
+            continue;
+        };
+        if match_info.status != db::MatchStatus::Finished {
+            continue;
+        }
+        let (winner, loser) = if match_info.result[0] > match_info.result[1] {
+            (match_info.team_a_id, match_info.team_b_id)
+        } else {
+            (match_info.team_b_id, match_info.team_a_id)
+        };
+        records[index[&winner]].wins += 1;
+        records[index[&loser]].losses += 1;
+    }
+    match tournament.format {
+        db::TournamentFormat::Swiss => records.sort_by(|a, b| {
+            b.wins
+                .cmp(&a.wins)
+                .then(a.losses.cmp(&b.losses))
+                .then(a.seed.cmp(&b.seed))
+        }),
+        db::TournamentFormat::DoubleElimination => records.sort_by(|a, b| {
+            a.losses
+                .cmp(&b.losses)
+                .then(b.wins.cmp(&a.wins))
+                .then(a.seed.cmp(&b.seed))
+        }),
+    }
+    records
+}
+
+/// Most pairing attempts tried before rematches are allowed, the search for pairings without
+/// rematches grows factorially with the number of teams once few of them are left
+const PAIRING_ATTEMPTS: usize = 100_000;
+
+/// Pairs the ranked teams, each one preferring the opponent halfway down its own group
+/// so the top half meets the bottom half, `floats` lets leftover teams meet the next group,
+/// gives up once `attempts` run out
+fn pair_up(
+    teams: &[&Record],
+    group: &dyn Fn(&Record) -> i32,
+    floats: bool,
+    rematches: bool,
+    attempts: &mut usize,
+) -> Option<Vec<(i32, Option<i32>)>> {
+    let Some((first, rest)) = teams.split_first() else {
+        return Some(Vec::new());
+    };
+    let (same, other): (Vec<usize>, Vec<usize>) =
+        (0..rest.len()).partition(|&i| group(rest[i]) == group(first));
+    let half = same.len().div_ceil(2);
+    let mut candidates: Vec<usize> = same[half.saturating_sub(1)..]
+        .iter()
+        .chain(same[..half.saturating_sub(1)].iter().rev())
+        .copied()
+        .collect();
+    if floats {
+        candidates.extend(other);
+    }
+    for candidate in candidates {
+        let opponent = rest[candidate];
+        if !rematches && first.opponents.contains(&opponent.team_id) {
+            continue;
+        }
+        if *attempts == 0 {
+            return None;
+        }
+        *attempts -= 1;
+        let remaining: Vec<&Record> = rest
+            .iter()
+            .enumerate()
+            .filter(|&(i, _)| i != candidate)
+            .map(|(_, record)| *record)
+            .collect();
+        if let Some(mut pairings) = pair_up(&remaining, group, floats, rematches, attempts) {
+            pairings.insert(0, (first.team_id, Some(opponent.team_id)));
+            return Some(pairings);
+        }
+    }
+    None
+}
+
+/// Teams with equal records meet each other without rematches, with an odd number of teams
+/// the lowest ranked team without a bye sits the round out; when every pairing has to repeat
+/// a match the tournament is over, when the search takes too long rematches are allowed
+pub fn swiss_pairings(records: &[Record]) -> Option<Vec<(i32, Option<i32>)>> {
+    let teams: Vec<&Record> = records.iter().collect();
+    let group = |record: &Record| record.wins;
+    let mut bye_candidates: Vec<Option<usize>> = if teams.len().is_multiple_of(2) {
+        vec![None]
+    } else {
+        let mut bye_candidates: Vec<usize> = (0..teams.len()).rev().collect();
+        bye_candidates.sort_by_key(|&i| teams[i].byes);
+        bye_candidates.into_iter().map(Some).collect()
+    };
+    let mut attempts = PAIRING_ATTEMPTS;
+    let mut unbounded = usize::MAX;
+    let pair_without = |bye: Option<usize>, rematches: bool, attempts: &mut usize| {
+        let rest: Vec<&Record> = teams
+            .iter()
+            .enumerate()
+            .filter(|&(i, _)| Some(i) != bye)
+            .map(|(_, record)| *record)
+            .collect();
+        let mut pairings = pair_up(&rest, &group, true, rematches, attempts)?;
+        pairings.extend(bye.map(|bye| (teams[bye].team_id, None)));
+        Some(pairings)
+    };
+    if let Some(pairings) = bye_candidates
+        .iter()
+        .find_map(|&bye| pair_without(bye, false, &mut attempts))
+    {
+        return Some(pairings);
+    }
+    if attempts > 0 {
+        return None;
+    }
+    pair_without(bye_candidates.remove(0), true, &mut unbounded)
+}
+
+/// Undefeated teams play in the winners bracket and teams with one loss in the losers bracket,
+/// the last team of each bracket meet in the final which is replayed if the undefeated team loses
+pub fn double_elimination_pairings(records: &[Record]) -> Option<Vec<(i32, Option<i32>)>> {
+    let alive: Vec<&Record> = records.iter().filter(|record| record.losses < 2).collect();
+    if alive.len() < 2 {
+        return None;
+    }
+    let brackets: [Vec<&Record>; 2] = [
+        alive.iter().copied().filter(|record| record.losses == 0).collect(),
+        alive.iter().copied().filter(|record| record.losses == 1).collect(),
+    ];
+    if brackets.iter().all(|teams| teams.len() <= 1) {
+        return Some(vec![(alive[0].team_id, Some(alive[1].team_id))]);
+    }
+    let mut pairings = Vec::new();
+    // A lone team waits in its bracket until the other one is decided
+    for teams in brackets.into_iter().filter(|teams| teams.len() > 1) {
+        let mut teams = teams;
+        if teams.len() % 2 == 1 {
+            let bye = (0..teams.len()).min_by_key(|&i| teams[i].byes).unwrap();
+            pairings.push((teams.remove(bye).team_id, None));
+        }
+        let group = |record: &Record| record.losses;
+        let mut attempts = PAIRING_ATTEMPTS;
+        let mut unbounded = usize::MAX;
+        pairings.extend(
+            pair_up(&teams, &group, false, false, &mut attempts)
+                .or_else(|| pair_up(&teams, &group, false, true, &mut unbounded))?,
+        );
+    }
+    Some(pairings)
+}
+
+#[derive(Debug)]
+pub enum NextRoundError {
+    UnknownTournament,
+    RoundNotFinished,
+    TournamentOver,
+    Date(db::MatchAddError),
+}
+
+impl Display for NextRoundError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NextRoundError::UnknownTournament => f.write_str("There is no such tournament"),
+            NextRoundError::RoundNotFinished => {
+                f.write_str("All matches of the current round have to be finished or cancelled")
+            }
+            NextRoundError::TournamentOver => {
+                f.write_str("No more rounds can be paired in this tournament")
+            }
+            NextRoundError::Date(e) => e.fmt(f),
+        }
+    }
+}
+
+impl Error for NextRoundError {}
+
+pub async fn next_round(
+    conn: &mut PgConnection,
+    tournament_id: i32,
+    date: &str,
+) -> Result<Vec<db::Match>, NextRoundError> {
+    // Pairing reads the previous rounds, so concurrent requests must not both get past it
+    if !db::lock_tournament(conn, tournament_id).await {
+        return Err(NextRoundError::UnknownTournament);
+    }
+    let Some(tournament) = db::get_tournament(conn, tournament_id).await else {
+        return Err(NextRoundError::UnknownTournament);
+    };
+    // A cancelled match is decided as neither a win nor a loss
+    let unfinished = tournament.pairings.iter().any(|pairing| {
+        pairing.match_info.as_ref().is_some_and(|match_info| {
+            !matches!(
+                match_info.status,
+                db::MatchStatus::Finished | db::MatchStatus::Cancelled
+            )
+        })
+    });
+    if unfinished {
+        return Err(NextRoundError::RoundNotFinished);
+    }
+    let date = db::parse_future_date(date).map_err(NextRoundError::Date)?;
+    let records = records(&tournament);
+    let pairings = match tournament.format {
+        db::TournamentFormat::Swiss => swiss_pairings(&records),
+        db::TournamentFormat::DoubleElimination => double_elimination_pairings(&records),
+    }
+    .ok_or(NextRoundError::TournamentOver)?;
+    Ok(db::add_tournament_round(conn, tournament_id, &pairings, date).await)
+}
+
+pub struct PoolPlaySetup<'a> {
+    pub name: &'a str,
+    pub pools: Vec<Vec<&'a str>>,
+    pub tie_breakers: Vec<db::TieBreaker>,
+    pub advancing: &'a str,
+    pub start: &'a str,
+    pub round_interval: &'a str,
+    pub ruleset: &'a str,
+    pub competition: &'a str,
+}
+
+#[derive(Debug)]
+pub enum PoolPlayError {
+    NameEmpty,
+    NameTooLong,
+    DuplicateName,
+    NoPools,
+    PoolTooSmall,
+    DuplicateTeam,
+    InvalidAdvancing,
+    InvalidRoundInterval,
+    Match(db::MatchAddError),
+}
+
+impl Display for PoolPlayError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PoolPlayError::NameEmpty => f.write_str("Tournament name cannot be empty"),
+            PoolPlayError::NameTooLong => {
+                f.write_str("Tournament name can't be longer than 50 characters")
+            }
+            PoolPlayError::DuplicateName => {
+                f.write_str("A tournament with this name already exists")
+            }
+            PoolPlayError::NoPools => f.write_str("At least one pool is needed"),
+            PoolPlayError::PoolTooSmall => f.write_str("Every pool needs at least two teams"),
+            PoolPlayError::DuplicateTeam => f.write_str("Every team can be listed only once"),
+            PoolPlayError::InvalidAdvancing => {
+                f.write_str("Teams advancing has to be between 1 and the size of the smallest pool")
+            }
+            PoolPlayError::InvalidRoundInterval => {
+                f.write_str("Minutes between rounds have to be between 1 and 10080")
+            }
+            PoolPlayError::Match(e) => e.fmt(f),
+        }
+    }
+}
+
+impl Error for PoolPlayError {}
+
+/// Creates the pools and their round-robin matches, rounds follow each other every `round_interval` minutes
+pub async fn create_pool_play(
+    conn: &mut PgConnection,
+    setup: &PoolPlaySetup<'_>,
+) -> Result<db::PoolPlay, PoolPlayError> {
+    let name = setup.name.trim();
+    if name.is_empty() {
+        return Err(PoolPlayError::NameEmpty);
+    }
+    if name.chars().count() > 50 {
+        return Err(PoolPlayError::NameTooLong);
+    }
+    if setup.pools.is_empty() {
+        return Err(PoolPlayError::NoPools);
+    }
+    if setup.pools.iter().any(|pool| pool.len() < 2) {
+        return Err(PoolPlayError::PoolTooSmall);
+    }
+    let teams: Vec<&str> = setup.pools.iter().flatten().copied().collect();
+    let keys: HashSet<String> = teams.iter().map(|team| db::team_key(team)).collect();
+    if keys.len() != teams.len() {
+        return Err(PoolPlayError::DuplicateTeam);
+    }
+    let smallest = setup.pools.iter().map(Vec::len).min().unwrap();
+    let advancing = match setup.advancing.trim().parse::<i32>() {
+        Ok(advancing) if advancing >= 1 && advancing as usize <= smallest => advancing,
+        _ => return Err(PoolPlayError::InvalidAdvancing),
+    };
+    let round_interval = match setup.round_interval.trim().parse::<i64>() {
+        Ok(minutes) if (1..=10080).contains(&minutes) => minutes,
+        _ => return Err(PoolPlayError::InvalidRoundInterval),
+    };
+    let start = db::parse_future_date(setup.start).map_err(PoolPlayError::Match)?;
+    let competition_id = db::parse_competition(conn, setup.competition)
+        .await
+        .map_err(PoolPlayError::Match)?;
+    let Some(pool_play_id) =
+        db::add_pool_play(conn, name, &setup.tie_breakers, advancing, competition_id).await
+    else {
+        return Err(PoolPlayError::DuplicateName);
+    };
+    db::lock_teams(conn, &teams).await;
+    let format = format_description::parse("[year]-[month]-[day]T[hour]:[minute]").unwrap();
+    for (index, teams) in setup.pools.iter().enumerate() {
+        let pool_id = db::add_pool(conn, pool_play_id, &format!("Pool {}", index + 1)).await;
+        for (round, pairings) in round_robin(teams.len(), false).into_iter().enumerate() {
+            let date = (start + Duration::minutes(round as i64 * round_interval))
+                .format(&format)
+                .unwrap();
+            for (home, away) in pairings {
+                let match_info = db::add_match(
+                    conn,
+                    teams[home],
+                    teams[away],
+                    &date,
+                    setup.ruleset,
+                    setup.competition,
+                    "",
+                )
+                .await
+                .map_err(PoolPlayError::Match)?;
+                db::add_pool_match(conn, pool_id, match_info.id).await;
+            }
+        }
+    }
+    Ok(db::get_pool_play(conn, pool_play_id).await.unwrap())
+}
+
+#[derive(Debug)]
+pub enum PlayoffsError {
+    UnknownPoolPlay,
+    PoolsNotFinished,
+    AlreadyCreated,
+    Bracket(db::BracketAddError),
+}
+
+impl Display for PlayoffsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PlayoffsError::UnknownPoolPlay => f.write_str("There is no such tournament"),
+            PlayoffsError::PoolsNotFinished => {
+                f.write_str("All pool matches have to be finished or cancelled before the playoffs")
+            }
+            PlayoffsError::AlreadyCreated => f.write_str("The playoffs were already created"),
+            PlayoffsError::Bracket(e) => e.fmt(f),
+        }
+    }
+}
+
+impl Error for PlayoffsError {}
+
+/// Seeds the playoff bracket with the pool winners first, then the runners-up and so on,
+/// teams with the same place keep the order of their pools
+pub async fn create_playoffs(
+    conn: &mut PgConnection,
+    pool_play_id: i32,
+    first_round: &str,
+    round_interval: &str,
+    ruleset: &str,
+) -> Result<db::Bracket, PlayoffsError> {
+    let Some(pool_play) = db::get_pool_play(conn, pool_play_id).await else {
+        return Err(PlayoffsError::UnknownPoolPlay);
+    };
+    if pool_play.bracket_id.is_some() {
+        return Err(PlayoffsError::AlreadyCreated);
+    }
+    if !pool_play.finished() {
+        return Err(PlayoffsError::PoolsNotFinished);
+    }
+    let tables: Vec<Vec<standings::Standing>> = pool_play
+        .pools
+        .iter()
+        .map(|pool| standings::ranked(&pool.matches, &pool_play.tie_breakers))
+        .collect();
+    let teams: Vec<&str> = (0..pool_play.advancing as usize)
+        .flat_map(|place| tables.iter().filter_map(move |table| table.get(place)))
+        .map(|standing| standing.team.as_str())
+        .collect();
+    let competition = pool_play
+        .competition_id
+        .map(|competition_id| competition_id.to_string())
+        .unwrap_or_default();
+    let bracket = db::add_bracket(
+        conn,
+        &pool_play.name,
+        &teams,
+        first_round,
+        round_interval,
+        ruleset,
+        &competition,
+    )
+    .await
+    .map_err(PlayoffsError::Bracket)?;
+    if !db::set_pool_play_bracket(conn, pool_play_id, bracket.id).await {
+        return Err(PlayoffsError::AlreadyCreated);
+    }
+    Ok(bracket)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn meetings(rounds: &[Vec<(usize, usize)>]) -> HashMap<(usize, usize), usize> {
+        let mut meetings = HashMap::new();
+        for &(home, away) in rounds.iter().flatten() {
+            *meetings.entry((home, away)).or_insert(0) += 1;
+        }
+        meetings
+    }
+
+    fn record(team_id: i32, wins: i32, losses: i32, opponents: &[i32]) -> Record {
+        Record {
+            team_id,
+            team: format!("Team {team_id}"),
+            seed: team_id,
+            wins,
+            losses,
+            byes: 0,
+            opponents: opponents.iter().copied().collect(),
+        }
+    }
+
+    fn assert_each_team_once(records: &[Record], pairings: &[(i32, Option<i32>)]) {
+        let mut teams: Vec<i32> = pairings
+            .iter()
+            .flat_map(|&(team_a, team_b)| [Some(team_a), team_b])
+            .flatten()
+            .collect();
+        teams.sort();
+        let mut expected: Vec<i32> = records.iter().map(|record| record.team_id).collect();
+        expected.sort();
+        assert_eq!(teams, expected);
+    }
+
+    #[test]
+    fn round_robin_pairs_every_team_once() {
+        for team_count in 2..=9 {
+            let rounds = round_robin(team_count, false);
+            let meetings = meetings(&rounds);
+            for a in 0..team_count {
+                for b in a + 1..team_count {
+                    let count = meetings.get(&(a, b)).unwrap_or(&0) + meetings.get(&(b, a)).unwrap_or(&0);
+                    assert_eq!(count, 1, "{team_count} teams, {a} and {b}");
+                }
+            }
+            for round in &rounds {
+                let teams: HashSet<usize> = round.iter().flat_map(|&(home, away)| [home, away]).collect();
+                assert_eq!(teams.len(), round.len() * 2);
+            }
+        }
+    }
+
+    #[test]
+    fn double_round_robin_plays_home_and_away() {
+        for team_count in 2..=8 {
+            let rounds = round_robin(team_count, true);
+            let meetings = meetings(&rounds);
+            for a in 0..team_count {
+                for b in 0..team_count {
+                    if a != b {
+                        assert_eq!(meetings.get(&(a, b)), Some(&1), "{team_count} teams, {a} at home to {b}");
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn round_robin_gives_every_team_one_bye_for_odd_counts() {
+        for team_count in [3, 5, 7, 9] {
+            let rounds = round_robin(team_count, false);
+            assert_eq!(rounds.len(), team_count);
+            let mut byes = vec![0; team_count];
+            for round in &rounds {
+                assert_eq!(round.len(), team_count / 2);
+                for (team, count) in byes.iter_mut().enumerate() {
+                    if !round.iter().any(|&(home, away)| home == team || away == team) {
+                        *count += 1;
+                    }
+                }
+            }
+            assert!(byes.iter().all(|&count| count == 1), "{team_count} teams: {byes:?}");
+        }
+    }
+
+    #[test]
+    fn swiss_gives_the_bye_to_the_lowest_ranked_team_without_one() {
+        let mut records: Vec<Record> = (1..=5).map(|team| record(team, 0, 0, &[])).collect();
+        let pairings = swiss_pairings(&records).unwrap();
+        assert_each_team_once(&records, &pairings);
+        assert!(pairings.contains(&(5, None)));
+
+        records[4].byes = 1;
+        let pairings = swiss_pairings(&records).unwrap();
+        assert_each_team_once(&records, &pairings);
+        assert!(pairings.contains(&(4, None)));
+    }
+
+    #[test]
+    fn swiss_avoids_rematches_until_every_pairing_repeats_one() {
+        let records = vec![
+            record(1, 1, 0, &[2]),
+            record(2, 0, 1, &[1]),
+            record(3, 1, 0, &[4]),
+            record(4, 0, 1, &[3]),
+        ];
+        let pairings = swiss_pairings(&records).unwrap();
+        assert_eq!(pairings, vec![(1, Some(3)), (2, Some(4))]);
+
+        let records: Vec<Record> = (1..=4)
+            .map(|team| {
+                let opponents: Vec<i32> = (1..=4).filter(|&other| other != team).collect();
+                record(team, 0, 0, &opponents)
+            })
+            .collect();
+        assert!(swiss_pairings(&records).is_none());
+    }
+
+    #[test]
+    fn swiss_allows_a_rematch_when_the_search_runs_out() {
+        // Odd and even teams have played each other and there is an odd number of both,
+        // so no pairing without a rematch exists but proving it takes too long
+        let records: Vec<Record> = (1..=22)
+            .map(|team| {
+                let opponents: Vec<i32> = (1..=22).filter(|other| (team + other) % 2 == 1).collect();
+                record(team, 0, 0, &opponents)
+            })
+            .collect();
+        let pairings = swiss_pairings(&records).unwrap();
+        assert_eq!(pairings.len(), 11);
+        assert_each_team_once(&records, &pairings);
+    }
+
+    #[test]
+    fn double_elimination_gives_a_bye_in_an_odd_bracket() {
+        let records: Vec<Record> = (1..=3).map(|team| record(team, 0, 0, &[])).collect();
+        let pairings = double_elimination_pairings(&records).unwrap();
+        assert_eq!(pairings.len(), 2);
+        assert_eq!(pairings.iter().filter(|(_, team_b)| team_b.is_none()).count(), 1);
+        assert_each_team_once(&records, &pairings);
+    }
+
+    #[test]
+    fn double_elimination_replays_the_final_after_the_first_loss() {
+        let records = vec![record(1, 3, 0, &[2]), record(2, 3, 1, &[1])];
+        assert_eq!(double_elimination_pairings(&records), Some(vec![(1, Some(2))]));
+
+        let records = vec![record(2, 4, 1, &[1]), record(1, 3, 1, &[2])];
+        assert_eq!(double_elimination_pairings(&records), Some(vec![(2, Some(1))]));
+
+        let records = vec![record(2, 5, 1, &[1]), record(1, 3, 2, &[2])];
+        assert_eq!(double_elimination_pairings(&records), None);
+    }
+}
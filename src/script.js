@@ -1,9 +1,9 @@
 let refresh_timer = null
 let clipboard_timer = null;
 let current_clipboard = null;
-let show_status = [true, true, true];
-const button_ids = ['#toggle_planned', '#toggle_in_progress', '#toggle_finished'];
-const match_status = ['Planned', 'InProgress', 'Finished'];
+let show_status = [true, true, true, true, true, true];
+const button_ids = ['#toggle_planned', '#toggle_in_progress', '#toggle_finished', '#toggle_postponed', '#toggle_suspended', '#toggle_cancelled'];
+const match_status = ['Planned', 'InProgress', 'Finished', 'Postponed', 'Suspended', 'Cancelled'];
 
 function close_error() {
   document.querySelector('#error').innerHTML = '';
@@ -29,7 +29,7 @@ function check_empty() {
 }
 
 function matches_load() {
-  for (let i = 0; i < 3; ++i) {
+  for (let i = 0; i < button_ids.length; ++i) {
     let button = document.querySelector(button_ids[i]);
     button.classList.toggle('bg-sky-500', show_status[i]);
     button.classList.toggle('hover:bg-sky-400', show_status[i]);
@@ -55,7 +55,7 @@ function matches_load() {
 function matches_update() {
   let match_list = document.querySelector('#match_list');
   Array.from(match_list.children).sort(cmp).forEach(e => match_list.appendChild(e));
-  for (let i = 0; i < 3; ++i) {
+  for (let i = 0; i < button_ids.length; ++i) {
     Array.from(match_list.children).filter(e => e.children[4].innerHTML == match_status[i]).forEach(e => e.classList.toggle('hidden', !show_status[i]));
   }
   check_empty();
@@ -0,0 +1,263 @@
+use std::{
+    cmp::Ordering,
+    collections::{HashMap, HashSet},
+    mem,
+};
+
+use crate::db;
+
+pub struct Standing {
+    pub team_id: i32,
+    pub team: String,
+    pub played: i32,
+    pub won: i32,
+    pub lost: i32,
+    pub points: i32,
+    pub sets_won: i32,
+    pub sets_lost: i32,
+    pub points_won: i32,
+    pub points_lost: i32,
+}
+
+impl Standing {
+    fn new(team_id: i32, team: &str) -> Standing {
+        Standing {
+            team_id,
+            team: team.to_owned(),
+            played: 0,
+            won: 0,
+            lost: 0,
+            points: 0,
+            sets_won: 0,
+            sets_lost: 0,
+            points_won: 0,
+            points_lost: 0,
+        }
+    }
+
+    pub fn sets_ratio(&self) -> f64 {
+        ratio(self.sets_won, self.sets_lost)
+    }
+
+    pub fn points_ratio(&self) -> f64 {
+        ratio(self.points_won, self.points_lost)
+    }
+}
+
+fn ratio(won: i32, lost: i32) -> f64 {
+    match (won, lost) {
+        (0, 0) => 0.0,
+        (_, 0) => f64::INFINITY,
+        _ => won as f64 / lost as f64,
+    }
+}
+
+/// FIVB match points: 3 for a win by two or more sets, 2 for a win in the deciding set
+/// and 1 for losing it, forfeits count as a clear win
+pub fn match_points(match_info: &db::Match) -> [i32; 2] {
+    let winner = if match_info.result[0] > match_info.result[1] {
+        0
+    } else {
+        1
+    };
+    let close = match_info.result[0].abs_diff(match_info.result[1]) < 2
+        && !matches!(match_info.outcome, Some(db::Outcome::Forfeit(_)));
+    let mut points = [0, 0];
+    points[winner] = if close { 2 } else { 3 };
+    points[1 - winner] = if close { 1 } else { 0 };
+    points
+}
+
+/// Standings of every team playing in `matches`, only finished matches count
+fn table<'a>(matches: impl IntoIterator<Item = &'a db::Match>) -> Vec<Standing> {
+    let mut table: HashMap<i32, Standing> = HashMap::new();
+    for match_info in matches {
+        let team_ids = [match_info.team_a_id, match_info.team_b_id];
+        let team_names = [&match_info.team_a, &match_info.team_b];
+        for side in 0..2 {
+            table
+                .entry(team_ids[side])
+                .or_insert_with(|| Standing::new(team_ids[side], team_names[side]));
+        }
+        if match_info.status != db::MatchStatus::Finished {
+            continue;
+        }
+        let points = match_points(match_info);
+        let rally_points = [
+            match_info.set_results_a.iter().sum::<i32>(),
+            match_info.set_results_b.iter().sum::<i32>(),
+        ];
+        for side in 0..2 {
+            let opponent = 1 - side;
+            let standing = table.get_mut(&team_ids[side]).unwrap();
+            standing.played += 1;
+            if match_info.result[side] > match_info.result[opponent] {
+                standing.won += 1;
+            } else {
+                standing.lost += 1;
+            }
+            standing.points += points[side];
+            standing.sets_won += match_info.result[side];
+            standing.sets_lost += match_info.result[opponent];
+            standing.points_won += rally_points[side];
+            standing.points_lost += rally_points[opponent];
+        }
+    }
+    table.into_values().collect()
+}
+
+pub struct HeadToHead {
+    pub standings: [Standing; 2],
+    pub meetings: Vec<db::Match>,
+}
+
+/// Record of one team over `matches`, empty when it has no finished match among them
+pub fn team_standing(matches: &[db::Match], team: &db::TeamInfo) -> Standing {
+    table(matches)
+        .into_iter()
+        .find(|standing| standing.team_id == team.id)
+        .unwrap_or_else(|| Standing::new(team.id, &team.name))
+}
+
+/// Wins, sets and rally points of two teams over their meetings, in the order of `teams`
+pub fn head_to_head(meetings: Vec<db::Match>, teams: [&db::TeamInfo; 2]) -> HeadToHead {
+    let standings = teams.map(|team| team_standing(&meetings, team));
+    HeadToHead {
+        standings,
+        meetings,
+    }
+}
+
+pub fn standings(matches: &[db::Match]) -> Vec<Standing> {
+    let mut standings = table(matches);
+    standings.retain(|standing| standing.played > 0);
+    standings.sort_by(|a, b| {
+        b.points
+            .cmp(&a.points)
+            .then(b.won.cmp(&a.won))
+            .then(
+                b.sets_ratio()
+                    .partial_cmp(&a.sets_ratio())
+                    .unwrap_or(Ordering::Equal),
+            )
+            .then(
+                b.points_ratio()
+                    .partial_cmp(&a.points_ratio())
+                    .unwrap_or(Ordering::Equal),
+            )
+            .then(a.team.cmp(&b.team))
+    });
+    standings
+}
+
+/// Ranks every team by the tie-breakers in order, head-to-head only counts
+/// the match points from matches among the teams still level
+pub fn ranked(matches: &[db::Match], tie_breakers: &[db::TieBreaker]) -> Vec<Standing> {
+    let mut standings = table(matches);
+    standings.sort_by(|a, b| a.team.cmp(&b.team));
+    rank(standings, matches, tie_breakers)
+}
+
+fn rank(
+    mut standings: Vec<Standing>,
+    matches: &[db::Match],
+    tie_breakers: &[db::TieBreaker],
+) -> Vec<Standing> {
+    let Some((tie_breaker, rest)) = tie_breakers.split_first() else {
+        return standings;
+    };
+    if standings.len() < 2 {
+        return standings;
+    }
+    let keys: HashMap<i32, f64> = match tie_breaker {
+        db::TieBreaker::MatchPoints => standings
+            .iter()
+            .map(|standing| (standing.team_id, standing.points as f64))
+            .collect(),
+        db::TieBreaker::SetsRatio => standings
+            .iter()
+            .map(|standing| (standing.team_id, standing.sets_ratio()))
+            .collect(),
+        db::TieBreaker::PointsRatio => standings
+            .iter()
+            .map(|standing| (standing.team_id, standing.points_ratio()))
+            .collect(),
+        db::TieBreaker::HeadToHead => {
+            let level: HashSet<i32> = standings.iter().map(|standing| standing.team_id).collect();
+            table(matches.iter().filter(|match_info| {
+                level.contains(&match_info.team_a_id) && level.contains(&match_info.team_b_id)
+            }))
+            .into_iter()
+            .map(|standing| (standing.team_id, standing.points as f64))
+            .collect()
+        }
+    };
+    let key = |standing: &Standing| keys.get(&standing.team_id).copied().unwrap_or(0.0);
+    standings.sort_by(|a, b| key(b).partial_cmp(&key(a)).unwrap_or(Ordering::Equal));
+    // Teams still level after a split start over, so head-to-head only looks at them
+    let split = key(&standings[0]) != key(&standings[standings.len() - 1]);
+    let next = if split { tie_breakers } else { rest };
+    let mut ranked = Vec::new();
+    let mut level = Vec::new();
+    for standing in standings {
+        if level.last().is_some_and(|last| key(last) != key(&standing)) {
+            ranked.extend(rank(mem::take(&mut level), matches, next));
+        }
+        level.push(standing);
+    }
+    ranked.extend(rank(level, matches, next));
+    ranked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const A: (i32, &str) = (1, "A");
+    const B: (i32, &str) = (2, "B");
+    const C: (i32, &str) = (3, "C");
+    const D: (i32, &str) = (4, "D");
+
+    fn order(standings: &[Standing]) -> Vec<&str> {
+        standings.iter().map(|standing| standing.team.as_str()).collect()
+    }
+
+    #[test]
+    fn head_to_head_starts_over_among_teams_still_level() {
+        // A, B and C have 5 match points each, among themselves A has 5 and B and C 2 each,
+        // B beat C, though C has the better sets ratio overall
+        let matches = [
+            db::Match::finished(1, [A, B], [3, 0]),
+            db::Match::finished(2, [A, C], [3, 2]),
+            db::Match::finished(3, [B, C], [3, 2]),
+            db::Match::finished(4, [B, D], [3, 1]),
+            db::Match::finished(5, [C, D], [3, 0]),
+        ];
+        let tie_breakers = [
+            db::TieBreaker::MatchPoints,
+            db::TieBreaker::HeadToHead,
+            db::TieBreaker::SetsRatio,
+        ];
+        let standings = ranked(&matches, &tie_breakers);
+        assert_eq!(order(&standings), ["A", "B", "C", "D"]);
+        assert!(standings[..3].iter().all(|standing| standing.points == 5));
+        assert!(standings[2].sets_ratio() > standings[1].sets_ratio());
+    }
+
+    #[test]
+    fn tie_breakers_apply_in_order() {
+        // A and B both win twice 3:0 and lose to each other, C has no points
+        let matches = [
+            db::Match::finished(1, [A, B], [3, 2]),
+            db::Match::finished(2, [A, C], [3, 0]),
+            db::Match::finished(3, [B, C], [3, 0]),
+        ];
+        let by_points = ranked(&matches, &[db::TieBreaker::MatchPoints]);
+        assert_eq!(order(&by_points), ["A", "B", "C"]);
+        let by_sets = ranked(&matches, &[db::TieBreaker::SetsRatio]);
+        assert_eq!(order(&by_sets), ["A", "B", "C"]);
+        let level = ranked(&matches[1..], &db::TieBreaker::ALL);
+        assert_eq!(order(&level), ["A", "B", "C"]);
+        assert_eq!(level[0].points, level[1].points);
+    }
+}
@@ -5,17 +5,19 @@ use futures_util::{SinkExt, StreamExt};
 use tokio::sync::Mutex;
 use uuid::Uuid;
 
-use crate::{db, markup};
+use crate::{db, markup, schedule, standings};
 
 pub async fn ws_handler(state: crate::AppState, ws: WebSocket) {
     let uuid = Uuid::new_v4();
     let (mut sink, mut stream) = ws.split();
+    let mut conn = state.pool.acquire().await.unwrap();
+    let season = db::current_season(&mut conn).await;
     sink.send(Message::Text(
-        markup::match_list(&db::get_matches(&mut state.pool.acquire().await.unwrap()).await)
-            .into_string(),
+        markup::match_list(&db::get_matches(&mut conn, season.id).await).into_string(),
     ))
     .await
     .unwrap();
+    drop(conn);
     {
         let mut list = state.clients.0.write().await;
         println!("Connected ws: {uuid}");
@@ -27,7 +29,7 @@ pub async fn ws_handler(state: crate::AppState, ws: WebSocket) {
             })),
         );
     }
-    while let Some(_) = stream.next().await {}
+    while stream.next().await.is_some() {}
     {
         let mut list = state.clients.0.write().await;
         println!("Disconnected ws: {uuid}");
@@ -57,10 +59,154 @@ pub async fn match_ws_handler(id: i32, state: crate::AppState, ws: WebSocket) {
             })),
         );
     }
-    while let Some(_) = stream.next().await {}
+    while stream.next().await.is_some() {}
     {
         let mut list = state.clients.0.write().await;
         println!("Disconnected match_ws({id}): {uuid}");
         list.remove(&uuid);
     }
 }
+
+pub async fn competition_ws_handler(id: i32, state: crate::AppState, ws: WebSocket) {
+    let uuid = Uuid::new_v4();
+    let (mut sink, mut stream) = ws.split();
+    sink.send(Message::Text(
+        markup::standings_table(&standings::standings(
+            &db::get_competition_matches(&mut state.pool.acquire().await.unwrap(), id).await,
+        ))
+        .into_string(),
+    ))
+    .await
+    .unwrap();
+    {
+        let mut list = state.clients.0.write().await;
+        println!("Connected competition_ws({id}): {uuid}");
+        list.insert(
+            uuid,
+            Arc::new(Mutex::new(crate::Client {
+                view: crate::ClientView::Competition(id),
+                sink,
+            })),
+        );
+    }
+    while stream.next().await.is_some() {}
+    {
+        let mut list = state.clients.0.write().await;
+        println!("Disconnected competition_ws({id}): {uuid}");
+        list.remove(&uuid);
+    }
+}
+
+pub async fn bracket_ws_handler(id: i32, state: crate::AppState, ws: WebSocket) {
+    let uuid = Uuid::new_v4();
+    let (mut sink, mut stream) = ws.split();
+    if let Some(bracket) = db::get_bracket(&mut state.pool.acquire().await.unwrap(), id).await {
+        sink.send(Message::Text(markup::bracket_view(&bracket).into_string()))
+            .await
+            .unwrap();
+    }
+    {
+        let mut list = state.clients.0.write().await;
+        println!("Connected bracket_ws({id}): {uuid}");
+        list.insert(
+            uuid,
+            Arc::new(Mutex::new(crate::Client {
+                view: crate::ClientView::Bracket(id),
+                sink,
+            })),
+        );
+    }
+    while stream.next().await.is_some() {}
+    {
+        let mut list = state.clients.0.write().await;
+        println!("Disconnected bracket_ws({id}): {uuid}");
+        list.remove(&uuid);
+    }
+}
+
+pub async fn tournament_ws_handler(id: i32, state: crate::AppState, ws: WebSocket) {
+    let uuid = Uuid::new_v4();
+    let (mut sink, mut stream) = ws.split();
+    if let Some(tournament) = db::get_tournament(&mut state.pool.acquire().await.unwrap(), id).await
+    {
+        sink.send(Message::Text(
+            markup::tournament_view(&tournament, &schedule::records(&tournament)).into_string(),
+        ))
+        .await
+        .unwrap();
+    }
+    {
+        let mut list = state.clients.0.write().await;
+        println!("Connected tournament_ws({id}): {uuid}");
+        list.insert(
+            uuid,
+            Arc::new(Mutex::new(crate::Client {
+                view: crate::ClientView::Tournament(id),
+                sink,
+            })),
+        );
+    }
+    while stream.next().await.is_some() {}
+    {
+        let mut list = state.clients.0.write().await;
+        println!("Disconnected tournament_ws({id}): {uuid}");
+        list.remove(&uuid);
+    }
+}
+
+pub async fn pool_play_ws_handler(id: i32, state: crate::AppState, ws: WebSocket) {
+    let uuid = Uuid::new_v4();
+    let (mut sink, mut stream) = ws.split();
+    if let Some(pool_play) = db::get_pool_play(&mut state.pool.acquire().await.unwrap(), id).await {
+        sink.send(Message::Text(markup::pool_play_view(&pool_play).into_string()))
+            .await
+            .unwrap();
+    }
+    {
+        let mut list = state.clients.0.write().await;
+        println!("Connected pool_play_ws({id}): {uuid}");
+        list.insert(
+            uuid,
+            Arc::new(Mutex::new(crate::Client {
+                view: crate::ClientView::PoolPlay(id),
+                sink,
+            })),
+        );
+    }
+    while stream.next().await.is_some() {}
+    {
+        let mut list = state.clients.0.write().await;
+        println!("Disconnected pool_play_ws({id}): {uuid}");
+        list.remove(&uuid);
+    }
+}
+
+pub async fn team_ws_handler(id: i32, state: crate::AppState, ws: WebSocket) {
+    let uuid = Uuid::new_v4();
+    let (mut sink, mut stream) = ws.split();
+    let mut conn = state.pool.acquire().await.unwrap();
+    if let Some(team) = db::get_team(&mut conn, id).await {
+        let matches = db::get_team_matches(&mut conn, id).await;
+        sink.send(Message::Text(markup::team_view(&team, &matches).into_string()))
+            .await
+            .unwrap();
+    }
+    drop(conn);
+    {
+        let mut list = state.clients.0.write().await;
+        println!("Connected team_ws({id}): {uuid}");
+        list.insert(
+            uuid,
+            Arc::new(Mutex::new(crate::Client {
+                view: crate::ClientView::Team(id),
+                sink,
+            })),
+        );
+    }
+    while stream.next().await.is_some() {}
+    {
+        let mut list = state.clients.0.write().await;
+        println!("Disconnected team_ws({id}): {uuid}");
+        list.remove(&uuid);
+    }
+}